@@ -0,0 +1,273 @@
+//! Statistics about how teams got on with each clue, for finding clues which were
+//! too hard (or too easy) once a game is over.
+
+use {
+    crate::{
+        anomaly,
+        clues::status::Status,
+        session::{
+            Session,
+            history::{GuessOutcome, HistoryEvent},
+        },
+    },
+    std::{
+        collections::{BTreeMap, HashMap, HashSet},
+        time::{Duration, SystemTime},
+    },
+};
+
+/// Number of most common wrong answers kept for each clue.
+const N_COMMON_WRONG_ANSWERS: usize = 3;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClueStatistics {
+    pub clue_id: String,
+    pub location: String,
+    /// Number of teams which have been given the clue.
+    pub teams_seen: usize,
+    pub solved: usize,
+    pub hinted: usize,
+    pub revealed: usize,
+    pub skipped: usize,
+    pub declined: usize,
+    /// Total number of wrong answers submitted while this was a team's current clue.
+    pub wrong_guesses: usize,
+    pub median_solve_duration: Option<Duration>,
+    /// The most common wrong answers with the number of teams which submitted them.
+    pub common_wrong_answers: Vec<(String, usize)>,
+}
+
+impl ClueStatistics {
+    pub fn solve_rate(&self) -> f64 {
+        self.rate(self.solved)
+    }
+
+    pub fn hint_rate(&self) -> f64 {
+        self.rate(self.hinted)
+    }
+
+    pub fn reveal_rate(&self) -> f64 {
+        self.rate(self.revealed)
+    }
+
+    pub fn skip_rate(&self) -> f64 {
+        self.rate(self.skipped)
+    }
+
+    pub fn decline_rate(&self) -> f64 {
+        self.rate(self.declined)
+    }
+
+    /// Fraction of the teams which saw the clue that `count` represents.
+    fn rate(&self, count: usize) -> f64 {
+        if self.teams_seen == 0 {
+            return 0.0;
+        }
+        count as f64 / self.teams_seen as f64
+    }
+}
+
+/// Selects the count in `ClueStatistics` to increase.
+type Counter = fn(&mut ClueStatistics) -> &mut usize;
+
+/// One action taken by a team, in the order they happened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimelineEntry {
+    pub time: SystemTime,
+    pub clue_id: String,
+    pub member: Option<String>,
+    pub event: HistoryEvent,
+}
+
+/// Statistics for every clue given to any of the `sessions`, sorted by clue ID.
+pub fn clue_statistics<'a, I>(sessions: I) -> Vec<ClueStatistics>
+where
+    I: IntoIterator<Item = &'a Session>,
+{
+    let mut by_clue: BTreeMap<String, ClueStatistics> = BTreeMap::new();
+    let mut durations: HashMap<String, Vec<Duration>> = HashMap::new();
+    let mut wrong_answers: HashMap<String, HashMap<String, usize>> = HashMap::new();
+
+    for session in sessions {
+        let ids_by_code: HashMap<[u8; 32], &str> = session
+            .clues()
+            .map(|(clue, _)| (clue.code, clue.id.as_str()))
+            .collect();
+        for (clue, status) in session.clues() {
+            let stats = by_clue
+                .entry(clue.id.clone())
+                .or_insert_with(|| ClueStatistics {
+                    clue_id: clue.id.clone(),
+                    location: clue.location.clone(),
+                    ..ClueStatistics::default()
+                });
+            match status {
+                Status::Unread => continue,
+                Status::Solved { duration, .. } => {
+                    stats.solved += 1;
+                    durations
+                        .entry(clue.id.clone())
+                        .or_default()
+                        .push(*duration);
+                }
+                Status::Declined => stats.declined += 1,
+                Status::Seen { .. } | Status::Skipped { .. } | Status::Pending { .. } => (),
+            }
+            stats.teams_seen += 1;
+        }
+
+        // Count each kind of help at most once per team.
+        let mut hinted = HashSet::new();
+        let mut revealed = HashSet::new();
+        let mut skipped = HashSet::new();
+        let mut team_wrong_answers = HashSet::new();
+        for entry in session.history() {
+            let Some(clue_id) = ids_by_code.get(&entry.clue_code) else {
+                continue;
+            };
+            match &entry.event {
+                HistoryEvent::Hint => {
+                    hinted.insert(*clue_id);
+                }
+                HistoryEvent::Reveal => {
+                    revealed.insert(*clue_id);
+                }
+                HistoryEvent::Skip => {
+                    skipped.insert(*clue_id);
+                }
+                HistoryEvent::Guess {
+                    answer,
+                    outcome: GuessOutcome::Wrong,
+                } => {
+                    if let Some(stats) = by_clue.get_mut(*clue_id) {
+                        stats.wrong_guesses += 1;
+                    }
+                    team_wrong_answers.insert((*clue_id, anomaly::normalize_answer(answer)));
+                }
+                HistoryEvent::Guess { .. }
+                | HistoryEvent::HelpRequest { .. }
+                | HistoryEvent::HelpReply { .. }
+                | HistoryEvent::PhotoSubmitted { .. }
+                | HistoryEvent::PhotoReviewed { .. } => (),
+            }
+        }
+        let counts: [(HashSet<&str>, Counter); 3] = [
+            (hinted, |stats| &mut stats.hinted),
+            (revealed, |stats| &mut stats.revealed),
+            (skipped, |stats| &mut stats.skipped),
+        ];
+        for (clue_ids, count) in counts {
+            for clue_id in clue_ids {
+                if let Some(stats) = by_clue.get_mut(clue_id) {
+                    *count(stats) += 1;
+                }
+            }
+        }
+        for (clue_id, answer) in team_wrong_answers {
+            *wrong_answers
+                .entry(clue_id.into())
+                .or_default()
+                .entry(answer)
+                .or_default() += 1;
+        }
+    }
+
+    by_clue
+        .into_values()
+        .map(|mut stats| {
+            stats.median_solve_duration = durations
+                .remove(&stats.clue_id)
+                .map(anomaly::median_duration);
+            if let Some(answers) = wrong_answers.remove(&stats.clue_id) {
+                let mut answers: Vec<(String, usize)> = answers.into_iter().collect();
+                answers.sort_by(|(a, x), (b, y)| y.cmp(x).then_with(|| a.cmp(b)));
+                answers.truncate(N_COMMON_WRONG_ANSWERS);
+                stats.common_wrong_answers = answers;
+            }
+            stats
+        })
+        .collect()
+}
+
+/// Everything a team did, in the order it happened.
+pub fn timeline(session: &Session) -> Vec<TimelineEntry> {
+    let ids_by_code: HashMap<[u8; 32], &str> = session
+        .clues()
+        .map(|(clue, _)| (clue.code, clue.id.as_str()))
+        .collect();
+    let mut entries: Vec<TimelineEntry> = session
+        .history()
+        .iter()
+        .map(|entry| TimelineEntry {
+            time: entry.time,
+            clue_id: ids_by_code
+                .get(&entry.clue_code)
+                .map_or_else(|| hex::encode(entry.clue_code), |id| String::from(*id)),
+            member: entry.member.clone(),
+            event: entry.event.clone(),
+        })
+        .collect();
+    entries.sort_by_key(|entry| entry.time);
+    entries
+}
+
+#[test]
+fn test_clue_statistics() {
+    use crate::{
+        clues::{Clues, team_code::AnswerMode},
+        session::SessionId,
+    };
+
+    let clues = Clues::mock();
+    let first = clues.0[0].clone();
+    let second = clues.0[1].clone();
+    let mut sessions: Vec<Session> = ["AAAA", "BBBB", "CCCC"]
+        .iter()
+        .map(|id| Session::new(SessionId::new(id).unwrap(), clues.clone()))
+        .collect();
+
+    // The first team solves the first clue after a wrong guess, the second needs a
+    // hint and gives up, the third has not started yet.
+    sessions[0].current_clue();
+    sessions[0].try_solve("nonsense", &AnswerMode::Shared, None);
+    sessions[0].try_solve(&first.id, &AnswerMode::Shared, None);
+    sessions[0].current_clue();
+    sessions[1].current_clue();
+    sessions[1].try_solve(" Nonsense", &AnswerMode::Shared, None);
+    sessions[1].hint_current_clue(None);
+    sessions[1].skip_current_clue(None);
+    sessions[1].current_clue();
+
+    let statistics = clue_statistics(&sessions);
+    let first_stats = statistics
+        .iter()
+        .find(|stats| stats.clue_id == first.id)
+        .unwrap();
+    assert_eq!(first_stats.teams_seen, 2);
+    assert_eq!(first_stats.solved, 1);
+    assert_eq!(first_stats.hinted, 1);
+    assert_eq!(first_stats.skipped, 1);
+    assert_eq!(first_stats.declined, 0);
+    assert_eq!(first_stats.wrong_guesses, 2);
+    assert_eq!(first_stats.solve_rate(), 0.5);
+    assert!(first_stats.median_solve_duration.is_some());
+    assert_eq!(
+        first_stats.common_wrong_answers,
+        vec![(String::from("nonsense"), 2)]
+    );
+
+    let second_stats = statistics
+        .iter()
+        .find(|stats| stats.clue_id == second.id)
+        .unwrap();
+    assert_eq!(
+        second_stats.teams_seen, 2,
+        "Both teams moved on to the second clue"
+    );
+    assert_eq!(second_stats.median_solve_duration, None);
+
+    let timeline = timeline(&sessions[1]);
+    assert_eq!(timeline.len(), 3);
+    assert_eq!(timeline[1].event, HistoryEvent::Hint);
+    assert!(timeline.iter().all(|entry| entry.clue_id == first.id));
+}
@@ -0,0 +1,302 @@
+//! Detection of teams whose play suggests they are receiving answers from other teams.
+//!
+//! Three signals are considered:
+//! - solving clues much faster than the other teams who solved the same clue,
+//! - repeatedly solving clues shortly after one particular other team solved them,
+//! - submitting the same wrong answers as another team.
+
+use {
+    crate::{
+        clues::status::Status,
+        session::{
+            Session, SessionId,
+            history::{GuessOutcome, HistoryEvent},
+        },
+    },
+    std::{
+        collections::{BTreeSet, HashMap},
+        time::{Duration, SystemTime},
+    },
+};
+
+/// A solve is considered fast if it takes less than this fraction of the
+/// median time the other teams took on the same clue.
+const FAST_SOLVE_FRACTION: f64 = 0.25;
+
+/// Minimum number of other teams that must have solved a clue before
+/// a solve of that clue can be judged fast.
+const MIN_COMPARISON_SOLVES: usize = 3;
+
+/// Number of fast solves a team must have before it is flagged.
+const MIN_FAST_SOLVES: usize = 2;
+
+/// A solve follows another team's solve of the same clue if it happens within this window.
+const FOLLOW_WINDOW: Duration = Duration::from_secs(120);
+
+/// Number of times a team must follow the same other team before it is flagged.
+const MIN_FOLLOWS: usize = 3;
+
+/// Number of distinct wrong answers two teams must share before they are flagged.
+const MIN_SHARED_WRONG_ANSWERS: usize = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Anomaly {
+    pub session_id: SessionId,
+    pub kind: AnomalyKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnomalyKind {
+    /// The team solved `count` clues in less than a quarter of the median time of other teams.
+    FastSolves { count: usize },
+    /// The team solved `count` clues shortly after `leader` solved the same clue.
+    FollowsTeam { leader: SessionId, count: usize },
+    /// The team submitted the same wrong answers as `other`.
+    SharedWrongAnswers {
+        other: SessionId,
+        answers: Vec<String>,
+    },
+}
+
+/// The information about a team's session needed to detect anomalies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionRecord {
+    pub session_id: SessionId,
+    pub solves: Vec<SolveRecord>,
+    pub wrong_answers: BTreeSet<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolveRecord {
+    pub clue_code: [u8; 32],
+    pub duration: Duration,
+    pub time: SystemTime,
+}
+
+impl From<&Session> for SessionRecord {
+    fn from(session: &Session) -> Self {
+        let mut solve_times = HashMap::new();
+        let mut wrong_answers = BTreeSet::new();
+        for entry in session.history() {
+            let HistoryEvent::Guess { answer, outcome } = &entry.event else {
+                continue;
+            };
+            match outcome {
+                GuessOutcome::Correct => {
+                    solve_times.insert(entry.clue_code, entry.time);
+                }
+                GuessOutcome::Wrong => {
+                    wrong_answers.insert(normalize_answer(answer));
+                }
+                GuessOutcome::OtherClue => (),
+            }
+        }
+        let solves = session
+            .clues()
+            .filter_map(|(clue, status)| {
+                let Status::Solved { duration, .. } = status else {
+                    return None;
+                };
+                Some(SolveRecord {
+                    clue_code: clue.code,
+                    duration: *duration,
+                    time: *solve_times.get(&clue.code)?,
+                })
+            })
+            .collect();
+        Self {
+            session_id: session.id,
+            solves,
+            wrong_answers,
+        }
+    }
+}
+
+pub fn detect<'a, I>(sessions: I) -> Vec<Anomaly>
+where
+    I: IntoIterator<Item = &'a Session>,
+{
+    let records: Vec<SessionRecord> = sessions.into_iter().map(SessionRecord::from).collect();
+    detect_from_records(&records)
+}
+
+pub fn detect_from_records(records: &[SessionRecord]) -> Vec<Anomaly> {
+    let mut anomalies = Vec::new();
+    anomalies.extend(fast_solves(records));
+    anomalies.extend(follows(records));
+    anomalies.extend(shared_wrong_answers(records));
+    anomalies
+}
+
+fn fast_solves(records: &[SessionRecord]) -> impl Iterator<Item = Anomaly> {
+    let mut durations_by_clue: HashMap<[u8; 32], Vec<(SessionId, Duration)>> = HashMap::new();
+    for record in records {
+        for solve in &record.solves {
+            durations_by_clue
+                .entry(solve.clue_code)
+                .or_default()
+                .push((record.session_id, solve.duration));
+        }
+    }
+
+    records.iter().filter_map(move |record| {
+        let count = record
+            .solves
+            .iter()
+            .filter(|solve| {
+                let others: Vec<Duration> = durations_by_clue[&solve.clue_code]
+                    .iter()
+                    .filter(|(id, _)| id != &record.session_id)
+                    .map(|(_, duration)| *duration)
+                    .collect();
+                if others.len() < MIN_COMPARISON_SOLVES {
+                    return false;
+                }
+                let threshold = median_duration(others).mul_f64(FAST_SOLVE_FRACTION);
+                solve.duration < threshold
+            })
+            .count();
+        (count >= MIN_FAST_SOLVES).then_some(Anomaly {
+            session_id: record.session_id,
+            kind: AnomalyKind::FastSolves { count },
+        })
+    })
+}
+
+fn follows(records: &[SessionRecord]) -> Vec<Anomaly> {
+    let mut result = Vec::new();
+    for follower in records {
+        let mut counts: HashMap<SessionId, usize> = HashMap::new();
+        for solve in &follower.solves {
+            for leader in records {
+                if leader.session_id == follower.session_id {
+                    continue;
+                }
+                let is_following = leader.solves.iter().any(|other| {
+                    other.clue_code == solve.clue_code
+                        && solve
+                            .time
+                            .duration_since(other.time)
+                            .is_ok_and(|gap| gap <= FOLLOW_WINDOW)
+                });
+                if is_following {
+                    *counts.entry(leader.session_id).or_default() += 1;
+                }
+            }
+        }
+        for (leader, count) in counts {
+            if count >= MIN_FOLLOWS {
+                result.push(Anomaly {
+                    session_id: follower.session_id,
+                    kind: AnomalyKind::FollowsTeam { leader, count },
+                });
+            }
+        }
+    }
+    result
+}
+
+fn shared_wrong_answers(records: &[SessionRecord]) -> Vec<Anomaly> {
+    let mut result = Vec::new();
+    for (i, record) in records.iter().enumerate() {
+        for other in &records[(i + 1)..] {
+            let answers: Vec<String> = record
+                .wrong_answers
+                .intersection(&other.wrong_answers)
+                .cloned()
+                .collect();
+            if answers.len() >= MIN_SHARED_WRONG_ANSWERS {
+                result.push(Anomaly {
+                    session_id: record.session_id,
+                    kind: AnomalyKind::SharedWrongAnswers {
+                        other: other.session_id,
+                        answers,
+                    },
+                });
+            }
+        }
+    }
+    result
+}
+
+pub(crate) fn median_duration(mut durations: Vec<Duration>) -> Duration {
+    durations.sort();
+    let mid = durations.len() / 2;
+    if durations.len() % 2 == 0 {
+        (durations[mid - 1] + durations[mid]) / 2
+    } else {
+        durations[mid]
+    }
+}
+
+pub(crate) fn normalize_answer(answer: &str) -> String {
+    answer.trim().to_lowercase()
+}
+
+#[test]
+fn test_detect_anomalies() {
+    let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+    let clue_codes: Vec<[u8; 32]> = (0..4).map(|i| [i; 32]).collect();
+    let minutes = |m: u64| Duration::from_secs(60 * m);
+
+    // Three honest teams each take about 10 minutes per clue.
+    let mut records: Vec<SessionRecord> = ["AAAA", "BBBB", "CCCC"]
+        .iter()
+        .enumerate()
+        .map(|(i, id)| SessionRecord {
+            session_id: SessionId::new(id).unwrap(),
+            solves: clue_codes
+                .iter()
+                .enumerate()
+                .map(|(j, code)| SolveRecord {
+                    clue_code: *code,
+                    duration: minutes(10 + i as u64),
+                    time: start + minutes(30 * j as u64 + 10 * i as u64),
+                })
+                .collect(),
+            wrong_answers: BTreeSet::new(),
+        })
+        .collect();
+    records[0].wrong_answers = ["cat", "dog", "fish"].map(String::from).into();
+
+    // A cheating team solves each clue a minute after team A, taking only a minute.
+    let cheater = SessionId::new("ZZZZ").unwrap();
+    records.push(SessionRecord {
+        session_id: cheater,
+        solves: clue_codes
+            .iter()
+            .enumerate()
+            .map(|(j, code)| SolveRecord {
+                clue_code: *code,
+                duration: minutes(1),
+                time: start + minutes(30 * j as u64 + 1),
+            })
+            .collect(),
+        wrong_answers: ["dog", "fish"].map(String::from).into(),
+    });
+
+    let anomalies = detect_from_records(&records);
+    assert!(
+        anomalies.iter().all(|a| a.session_id != records[1].session_id
+            && a.session_id != records[2].session_id),
+        "Honest teams are not flagged: {anomalies:?}"
+    );
+    assert!(anomalies.contains(&Anomaly {
+        session_id: cheater,
+        kind: AnomalyKind::FastSolves { count: 4 },
+    }));
+    assert!(anomalies.contains(&Anomaly {
+        session_id: cheater,
+        kind: AnomalyKind::FollowsTeam {
+            leader: records[0].session_id,
+            count: 4
+        },
+    }));
+    assert!(anomalies.contains(&Anomaly {
+        session_id: records[0].session_id,
+        kind: AnomalyKind::SharedWrongAnswers {
+            other: cheater,
+            answers: vec!["dog".into(), "fish".into()],
+        },
+    }));
+}
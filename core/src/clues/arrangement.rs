@@ -7,14 +7,53 @@ use {
     std::collections::{HashMap, HashSet, VecDeque},
 };
 
-pub type CluesGenerator = std::iter::Chain<<Vec<Clues> as IntoIterator>::IntoIter, RandomOrder>;
+/// Number of upcoming clues compared when choosing an arrangement for a new team.
+const OVERLAP_HORIZON: usize = 4;
 
+/// Number of random arrangements considered for a new team, in addition to the
+/// precomputed arrangements.
+const N_RANDOM_CANDIDATES: usize = 16;
+
+/// Number of precomputed arrangements. At each step they all visit different locations.
+const N_ARRANGEMENTS: usize = 4;
+
+/// Number of tries at arranging the clues before giving up.
+const MAX_ATTEMPTS: usize = 1_000;
+
+#[derive(Debug, Clone)]
 pub struct Arrangements {
     inner: Vec<Clues>,
 }
 
 impl Arrangements {
-    pub fn new(clues: Clues) -> Self {
+    /// Check that `clues` can be arranged. At every step the precomputed arrangements
+    /// visit different locations, so each location can hold at most 1 in
+    /// `N_ARRANGEMENTS` of the clues.
+    fn check_locations(clues: &Clues) -> Result<(), String> {
+        let n_clues = clues.0.len();
+        if n_clues < N_ARRANGEMENTS {
+            return Err(format!(
+                "There are {n_clues} clues, but at least {N_ARRANGEMENTS} are needed"
+            ));
+        }
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for clue in &clues.0 {
+            *counts.entry(clue.location.as_str()).or_default() += 1;
+        }
+        match counts.into_iter().max_by_key(|(_, count)| *count) {
+            Some((location, count)) if count * N_ARRANGEMENTS > n_clues => Err(format!(
+                "{count} of the {n_clues} clues are at {location}, but at most {} can be \
+                at one location, so that teams are not sent to the same place",
+                n_clues / N_ARRANGEMENTS
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Arrange `clues`, failing if no arrangements are found which avoid sending teams
+    /// to the same location or visiting a location twice in a row.
+    pub fn new(clues: Clues) -> Result<Self, String> {
+        Self::check_locations(&clues)?;
         let n_clues = clues.0.len();
 
         let mut clues_by_location: HashMap<String, Vec<Clue>> = HashMap::new();
@@ -26,13 +65,13 @@ impl Arrangements {
         }
 
         // TODO: should be dynamic
-        let n_arrangements = 4;
+        let n_arrangements = N_ARRANGEMENTS;
 
         // Start with an arrangement that never repeats two locations in a row then
         // create new arrangements from the base arrangement by choosing a clue at a different
         // location from what was chosen in the other arrangements.
         let mut rng = rand::rng();
-        let base_arrangement = create_arrangement(&clues_by_location, n_clues, &mut rng);
+        let base_arrangement = create_arrangement(&clues_by_location, n_clues, &mut rng)?;
 
         let mut used_locations = HashSet::new();
 
@@ -63,10 +102,10 @@ impl Arrangements {
                     }) {
                         // Try again to pick arrangements that never repeat locations.
                         n_attempts += 1;
-                        if n_attempts == 1_000 {
-                            panic!(
-                                "Failed to generate other arrangements without repeating locations"
-                            );
+                        if n_attempts == MAX_ATTEMPTS {
+                            return Err(String::from(
+                                "Failed to arrange the clues so that teams are not sent to the same place",
+                            ));
                         }
                         continue 'outer;
                     } else {
@@ -87,40 +126,70 @@ impl Arrangements {
             break arrangements;
         };
 
-        Self {
+        Ok(Self {
             inner: arrangements.into_iter().map(Clues).collect(),
-        }
+        })
     }
 
-    pub fn iterator(self) -> CluesGenerator {
-        let random_clues = RandomOrder {
-            base: self.inner.first().unwrap().clone(),
-        };
-
-        // Start with the known arrangements, then give random orders.
-        self.inner.into_iter().chain(random_clues)
+    /// Choose the arrangement for a new team which sends it to the fewest locations
+    /// where other teams are expected to be. `others` holds the upcoming locations of
+    /// each team already playing (see `Session::upcoming_locations`), assuming that all
+    /// teams take about as long per clue.
+    pub fn least_overlapping(&self, others: &[Vec<&str>]) -> Clues {
+        let mut rng = rand::rng();
+        let base = &self.inner[0];
+        let random = (0..N_RANDOM_CANDIDATES)
+            .map(|_| {
+                let mut clues = base.clone();
+                clues.0.shuffle(&mut rng);
+                clues
+            })
+            .filter(|clues| {
+                clues
+                    .0
+                    .windows(2)
+                    .all(|pair| pair[0].location != pair[1].location)
+            });
+        // On a tie, the precomputed arrangements are preferred.
+        self.inner
+            .iter()
+            .cloned()
+            .chain(random)
+            .min_by_key(|candidate| overlap(candidate, others))
+            .expect("There is always at least one arrangement")
     }
-}
 
-pub struct RandomOrder {
-    base: Clues,
+    /// The precomputed arrangements, which new teams are given unless a random order
+    /// overlaps less with the other teams.
+    pub fn precomputed(&self) -> &[Clues] {
+        &self.inner
+    }
 }
 
-impl Iterator for RandomOrder {
-    type Item = Clues;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let mut rng = rand::rng();
-        self.base.0.shuffle(&mut rng);
-        Some(self.base.clone())
-    }
+/// How many times a team following `candidate` would be at the same location as
+/// another team. Overlaps at earlier clues are weighted more, since the guess of
+/// where the other teams will be becomes less reliable further ahead.
+fn overlap(candidate: &Clues, others: &[Vec<&str>]) -> usize {
+    candidate
+        .0
+        .iter()
+        .take(OVERLAP_HORIZON)
+        .enumerate()
+        .map(|(step, clue)| {
+            let collisions = others
+                .iter()
+                .filter(|locations| locations.get(step) == Some(&clue.location.as_str()))
+                .count();
+            collisions * (OVERLAP_HORIZON - step)
+        })
+        .sum()
 }
 
 fn create_arrangement(
     clues_by_location: &HashMap<String, Vec<Clue>>,
     n_clues: usize,
     rng: &mut ThreadRng,
-) -> Vec<Clue> {
+) -> Result<Vec<Clue>, String> {
     let mut n_attempts = 0;
     'outer: loop {
         let mut locations: Vec<String> = clues_by_location.keys().cloned().collect();
@@ -142,8 +211,10 @@ fn create_arrangement(
                 .count();
             if remaining_locations == 0 {
                 n_attempts += 1;
-                if n_attempts == 1_000 {
-                    panic!("Failed to find arrangement that does not repeat locations!");
+                if n_attempts == MAX_ATTEMPTS {
+                    return Err(String::from(
+                        "Failed to arrange the clues without visiting a location twice in a row",
+                    ));
                 }
                 continue 'outer;
             }
@@ -158,7 +229,7 @@ fn create_arrangement(
                 }
             }
         }
-        break arrangement;
+        break Ok(arrangement);
     }
 }
 
@@ -186,11 +257,10 @@ fn test_arrangements() {
     }
 
     let clues = Clues::mock();
-    let arrangements: Vec<Clues> = Arrangements::new(clues.clone())
-        .iterator()
-        .take(4)
-        .collect();
-    for arrangement in &arrangements {
+    let arrangements = Arrangements::new(clues.clone()).unwrap();
+    let arrangements = arrangements.precomputed();
+    assert_eq!(arrangements.len(), N_ARRANGEMENTS);
+    for arrangement in arrangements {
         assert!(
             are_same_clues(arrangement, &clues),
             "Arrangements should have same clues as input"
@@ -211,7 +281,7 @@ fn test_arrangements() {
     }
     for i in 0..clues.0.len() {
         let mut used_locations = HashSet::new();
-        for arrangement in &arrangements {
+        for arrangement in arrangements {
             used_locations.insert(&arrangement.0[i].location);
         }
         assert_eq!(
@@ -221,3 +291,54 @@ fn test_arrangements() {
         );
     }
 }
+
+#[test]
+fn test_least_overlapping() {
+    let clues = Clues::mock();
+    let arrangements = Arrangements::new(clues).unwrap();
+
+    // The first teams get the precomputed arrangements, which never overlap.
+    let mut teams: Vec<Clues> = Vec::new();
+    for _ in 0..4 {
+        let others: Vec<Vec<&str>> = teams
+            .iter()
+            .map(|team| team.0.iter().map(|c| c.location.as_str()).collect())
+            .collect();
+        let chosen = arrangements.least_overlapping(&others);
+        assert_eq!(overlap(&chosen, &others), 0);
+        teams.push(chosen);
+    }
+
+    // A late team avoids the location everyone else is currently at.
+    let others = vec![vec!["A", "B", "C"]; 5];
+    let chosen = arrangements.least_overlapping(&others);
+    assert_ne!(chosen.0[0].location, "A");
+}
+
+#[test]
+fn test_check_locations() {
+    fn clues_at(locations: &[&'static str]) -> Clues {
+        Clues(
+            locations
+                .iter()
+                .enumerate()
+                .map(|(seed, location)| Clue::mock(seed as u64, location))
+                .collect(),
+        )
+    }
+
+    assert_eq!(Arrangements::check_locations(&Clues::mock()), Ok(()));
+    assert!(Arrangements::check_locations(&Clues(Vec::new())).is_err());
+    assert!(
+        Arrangements::check_locations(&clues_at(&["A", "B", "C"])).is_err(),
+        "Fewer locations than arrangements"
+    );
+    assert!(
+        Arrangements::check_locations(&clues_at(&["A", "A", "A", "A", "B", "B", "C", "D"]))
+            .is_err(),
+        "Half the clues at one location"
+    );
+    let crowded = clues_at(&["A", "A", "A", "B", "B", "C", "C", "D", "E"]);
+    assert!(Arrangements::check_locations(&crowded).is_err());
+    assert!(Arrangements::new(crowded).is_err());
+}
@@ -0,0 +1,232 @@
+//! The kinds of answer a clue can have.
+
+use {
+    super::{Clue, answer_to_code, team_code::AnswerMode},
+    crate::session::SessionId,
+    serde::{Deserialize, Serialize},
+};
+
+/// How teams answer a clue. In the clue file this is e.g. `"kind": {"type": "photo"}`,
+/// and clues without a kind have a typed answer. Team-specific answer codes only
+/// apply to typed answers.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClueKind {
+    /// The team types the answer, which is checked against the clue's code.
+    #[default]
+    Text,
+    /// The team uploads a photo, which the game master approves or rejects. The
+    /// clue's answer only identifies it and cannot be typed.
+    Photo,
+    /// The team picks one of the `options`, one of which is the clue's answer.
+    /// After `max_attempts` wrong picks the clue is skipped forever.
+    MultipleChoice {
+        options: Vec<String>,
+        #[serde(default = "default_max_attempts")]
+        max_attempts: u32,
+    },
+    /// The team enters a number, which is correct if it is within `tolerance` of
+    /// the clue's answer.
+    Numeric {
+        /// The clue's answer as a number, filled in when the clue file is read.
+        #[serde(default)]
+        value: f64,
+        #[serde(default)]
+        tolerance: f64,
+    },
+    /// The team puts the `items`, listed in the order they are shown, into the right
+    /// order. The clue's answer is the items in that order, separated by commas.
+    Sequence { items: Vec<String> },
+}
+
+fn default_max_attempts() -> u32 {
+    1
+}
+
+impl ClueKind {
+    /// Whether the clue can be solved by submitting an answer, rather than a photo.
+    pub fn accepts_typed_answer(&self) -> bool {
+        !matches!(self, Self::Photo)
+    }
+
+    /// How many answers a team may submit, for clues with limited attempts.
+    pub fn max_attempts(&self) -> Option<u32> {
+        match self {
+            Self::MultipleChoice { max_attempts, .. } => Some(*max_attempts),
+            _ => None,
+        }
+    }
+
+    /// Check the kind against the clue's `answer` from the clue file, returning the
+    /// kind to use and the answer from which to derive the clue's code.
+    pub fn with_answer(self, answer: &str) -> Result<(Self, String), String> {
+        match self {
+            Self::Text | Self::Photo => Ok((self, answer.into())),
+            Self::MultipleChoice {
+                ref options,
+                max_attempts,
+            } => {
+                if !options.iter().any(|option| option == answer) {
+                    return Err(format!("The answer {answer:?} is not one of the options"));
+                }
+                if max_attempts == 0 {
+                    return Err(String::from("Teams must have at least one attempt"));
+                }
+                Ok((self, answer.into()))
+            }
+            Self::Numeric { tolerance, .. } => {
+                let value: f64 = answer
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("The answer {answer:?} is not a number"))?;
+                if !value.is_finite() || !tolerance.is_finite() || tolerance < 0.0 {
+                    return Err(String::from(
+                        "The answer and tolerance must be finite numbers",
+                    ));
+                }
+                Ok((Self::Numeric { value, tolerance }, answer.into()))
+            }
+            Self::Sequence { ref items } => {
+                let answer = canonical_sequence(answer);
+                let mut expected: Vec<&str> = answer.split(", ").collect();
+                let mut given: Vec<&str> = items.iter().map(|item| item.trim()).collect();
+                expected.sort_unstable();
+                given.sort_unstable();
+                if expected != given {
+                    return Err(String::from("The answer must list each item once"));
+                }
+                Ok((self, answer))
+            }
+        }
+    }
+}
+
+impl Clue {
+    /// Check the submitted answer in the way the clue's kind is answered.
+    pub fn is_correct(
+        &self,
+        mode: &AnswerMode,
+        session_id: &SessionId,
+        submitted_answer: &str,
+    ) -> bool {
+        match &self.kind {
+            ClueKind::Text => mode.is_correct(session_id, self, submitted_answer),
+            ClueKind::Photo => false,
+            ClueKind::MultipleChoice { .. } => answer_to_code(submitted_answer) == self.code,
+            ClueKind::Numeric { value, tolerance } => submitted_answer
+                .trim()
+                .parse::<f64>()
+                .is_ok_and(|number| (number - value).abs() <= *tolerance),
+            ClueKind::Sequence { .. } => {
+                answer_to_code(&canonical_sequence(submitted_answer)) == self.code
+            }
+        }
+    }
+}
+
+/// The items of a comma-separated sequence, trimmed and separated by `", "`.
+fn canonical_sequence(sequence: &str) -> String {
+    sequence
+        .split(',')
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[test]
+fn test_clue_kind_json() {
+    let kind: ClueKind = serde_json::from_str(r#"{"type": "photo"}"#).unwrap();
+    assert_eq!(kind, ClueKind::Photo);
+    assert!(!kind.accepts_typed_answer());
+    assert!(serde_json::from_str::<ClueKind>(r#"{"type": "smell"}"#).is_err());
+
+    let kind: ClueKind =
+        serde_json::from_str(r#"{"type": "multiple_choice", "options": ["a", "b"]}"#).unwrap();
+    assert_eq!(
+        kind,
+        ClueKind::MultipleChoice {
+            options: vec![String::from("a"), String::from("b")],
+            max_attempts: 1
+        }
+    );
+    let kind: ClueKind = serde_json::from_str(r#"{"type": "numeric", "tolerance": 0.5}"#).unwrap();
+    assert!(matches!(kind, ClueKind::Numeric { tolerance, .. } if tolerance == 0.5));
+}
+
+#[test]
+fn test_with_answer() {
+    let choice = ClueKind::MultipleChoice {
+        options: vec![String::from("red"), String::from("blue")],
+        max_attempts: 2,
+    };
+    assert!(choice.clone().with_answer("blue").is_ok());
+    assert!(
+        choice.with_answer("green").is_err(),
+        "The answer must be an option"
+    );
+
+    let numeric = ClueKind::Numeric {
+        value: 0.0,
+        tolerance: 0.5,
+    };
+    assert_eq!(
+        numeric.clone().with_answer(" 12.5").unwrap().0,
+        ClueKind::Numeric {
+            value: 12.5,
+            tolerance: 0.5
+        }
+    );
+    assert!(numeric.clone().with_answer("twelve").is_err());
+    assert!(numeric.with_answer("inf").is_err());
+
+    let sequence = ClueKind::Sequence {
+        items: vec![String::from("b"), String::from("a"), String::from("c")],
+    };
+    assert_eq!(
+        sequence.clone().with_answer("a,b , c").unwrap().1,
+        "a, b, c"
+    );
+    assert!(
+        sequence.clone().with_answer("a, b").is_err(),
+        "Every item is needed"
+    );
+    assert!(sequence.with_answer("a, b, b").is_err());
+}
+
+#[test]
+fn test_is_correct() {
+    let mode = AnswerMode::Shared;
+    let id = SessionId::new("TEST").unwrap();
+    let clue = |kind: ClueKind, answer: &str| {
+        let (kind, answer) = kind.with_answer(answer).unwrap();
+        Clue {
+            code: answer_to_code(&answer),
+            kind,
+            ..Clue::mock(0, "A")
+        }
+    };
+
+    let numeric = clue(
+        ClueKind::Numeric {
+            value: 0.0,
+            tolerance: 0.5,
+        },
+        "42",
+    );
+    assert!(numeric.is_correct(&mode, &id, "42"));
+    assert!(numeric.is_correct(&mode, &id, " 41.6 "));
+    assert!(!numeric.is_correct(&mode, &id, "41.4"));
+    assert!(!numeric.is_correct(&mode, &id, "forty-two"));
+
+    let sequence = clue(
+        ClueKind::Sequence {
+            items: vec![String::from("c"), String::from("a"), String::from("b")],
+        },
+        "a, b, c",
+    );
+    assert!(sequence.is_correct(&mode, &id, "a,b,c"));
+    assert!(!sequence.is_correct(&mode, &id, "b, a, c"));
+
+    let photo = clue(ClueKind::Photo, "0");
+    assert!(!photo.is_correct(&mode, &id, "0"), "Photos cannot be typed");
+}
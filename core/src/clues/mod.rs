@@ -1,20 +1,26 @@
 use {
-    self::status::KnowledgeKind,
+    self::{kind::ClueKind, status::KnowledgeKind},
     sha3::{Digest, Sha3_256},
-    std::{io, path::Path, time::Duration},
+    std::{collections::HashSet, io, path::Path, time::Duration},
 };
 
 pub mod arrangement;
+pub mod kind;
 mod on_disk;
+pub mod reload;
+pub mod scan;
 pub mod status;
+pub mod team_code;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Clue {
+    pub id: String,
     pub poem: String,
     pub hint: String,
     pub item: String,
     pub location: String,
     pub code: [u8; 32],
+    pub kind: ClueKind,
 }
 
 impl Clue {
@@ -25,34 +31,65 @@ impl Clue {
         let hint = poem.clone();
         let item = poem.clone();
         Self {
+            id: seed.to_string(),
             poem,
             hint,
             item,
             location: location.into(),
             code,
+            kind: ClueKind::Text,
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Clues(pub Vec<Clue>);
 
 impl Clues {
     pub fn from_disk(path: &Path) -> Result<Self, io::Error> {
-        let clues = on_disk::Clues::read_json(path)?
+        let clues = Self::from_disk_with_answers(path)?
+            .into_iter()
+            .map(|(clue, _)| clue)
+            .collect();
+        Ok(Self(clues))
+    }
+
+    /// Read the clues along with their answers as written in the clue file, e.g. for
+    /// printing labels. Only the clue's code is kept while the hunt runs.
+    pub fn from_disk_with_answers(path: &Path) -> Result<Vec<(Clue, String)>, io::Error> {
+        let clues: Vec<(Clue, String)> = on_disk::Clues::read_json(path)?
             .0
             .into_iter()
-            .map(|clue| Clue {
-                poem: clue.poem,
-                hint: clue.hint,
-                item: clue.item,
-                location: clue.location,
-                code: answer_to_code(&clue.answer),
-            });
-        Ok(Self(clues.collect()))
+            .enumerate()
+            .map(|(i, clue)| {
+                let (kind, answer) = clue.kind.with_answer(&clue.answer).map_err(|e| {
+                    io::Error::new(io::ErrorKind::InvalidData, format!("Clue {}: {e}", i + 1))
+                })?;
+                let code = answer_to_code(&answer);
+                let written_answer = clue.answer;
+                let clue = Clue {
+                    id: clue.id.unwrap_or_else(|| default_id(&code)),
+                    poem: clue.poem,
+                    hint: clue.hint,
+                    item: clue.item,
+                    location: clue.location,
+                    code,
+                    kind,
+                };
+                Ok((clue, written_answer))
+            })
+            .collect::<Result<_, io::Error>>()?;
+        let mut ids = HashSet::new();
+        if let Some((clue, _)) = clues.iter().find(|(clue, _)| !ids.insert(&clue.id)) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Duplicate clue ID {}", clue.id),
+            ));
+        }
+        Ok(clues)
     }
 
-    #[cfg(test)]
+    #[cfg(any(feature = "test-only", test))]
     pub fn mock() -> Self {
         Self(vec![
             Clue::mock(0, "A"),
@@ -73,26 +110,39 @@ impl Clues {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ClueView {
     pub clue: Clue,
+    /// Position of the clue in the team's arrangement, starting from 1.
+    pub number: usize,
     pub knowledge: KnowledgeKind,
     pub is_previously_skipped: bool,
     pub duration: Duration,
+    /// The game master's replies to the team's requests for help with this clue.
+    pub help_replies: Vec<String>,
+    /// True if the team asked the game master for help and is waiting for a reply.
+    pub awaiting_help: bool,
+    /// For clues with limited attempts, how many the team has left.
+    pub attempts_left: Option<u32>,
 }
 
 impl ClueView {
     pub fn new(
         clue: Clue,
+        number: usize,
         knowledge: KnowledgeKind,
         is_previously_skipped: bool,
         duration: Duration,
     ) -> Self {
         Self {
             clue,
+            number,
             knowledge,
             is_previously_skipped,
             duration,
+            help_replies: Vec::new(),
+            awaiting_help: false,
+            attempts_left: None,
         }
     }
 
@@ -109,6 +159,11 @@ impl ClueView {
     }
 }
 
+/// The ID of a clue which was not given one in the clue file.
+pub fn default_id(code: &[u8; 32]) -> String {
+    hex::encode(&code[..8])
+}
+
 pub fn answer_to_code(answer: &str) -> [u8; 32] {
     let mut hasher = Sha3_256::new();
     hasher.update(answer.as_bytes());
@@ -1,20 +1,27 @@
 //! Definition of the Clues as they exist on-disk.
 
 use {
+    super::kind::ClueKind,
     serde::{Deserialize, Serialize},
     std::{fs, io, path::Path},
 };
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ClueDefinition {
+    /// Stable identifier used to match clues when the file is reloaded.
+    /// Without one, a clue is identified by its answer.
+    #[serde(default)]
+    pub id: Option<String>,
     pub poem: String,
     pub hint: String,
     pub item: String,
     pub location: String,
     pub answer: String,
+    #[serde(default)]
+    pub kind: ClueKind,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Clues(pub Vec<ClueDefinition>);
 
 impl Clues {
@@ -0,0 +1,91 @@
+//! Reloading the clue file while a game is running.
+//!
+//! Clues in the new file are matched to the clues teams already have by their ID.
+//! The text of a matched clue can be updated freely, but changing its answer also
+//! changes what teams must submit (and their team codes), so callers should ask
+//! for confirmation before applying such changes.
+
+use {
+    crate::clues::{Clue, Clues},
+    std::collections::{BTreeSet, HashSet},
+};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClueDiff {
+    /// Clues whose poem, hint, item or location changed.
+    pub changed_text: BTreeSet<String>,
+    /// Clues whose answer, or kind of answer, changed.
+    pub changed_answer: BTreeSet<String>,
+    /// Clues missing from the new file. Teams which already have them keep their copy.
+    pub removed: BTreeSet<String>,
+    /// Clues only in the new file. They are only given to teams which register later.
+    pub added: BTreeSet<String>,
+}
+
+impl ClueDiff {
+    /// Compare the clues currently in use (which may include several copies of
+    /// the same clue) with newly loaded clues.
+    pub fn new<'a, I>(old: I, new: &Clues) -> Self
+    where
+        I: IntoIterator<Item = &'a Clue>,
+    {
+        let mut diff = Self::default();
+        let mut old_ids = HashSet::new();
+        for old_clue in old {
+            old_ids.insert(old_clue.id.as_str());
+            let Some(new_clue) = new.get(&old_clue.id) else {
+                diff.removed.insert(old_clue.id.clone());
+                continue;
+            };
+            if new_clue.code != old_clue.code || new_clue.kind != old_clue.kind {
+                diff.changed_answer.insert(old_clue.id.clone());
+            }
+            let is_text_changed = new_clue.poem != old_clue.poem
+                || new_clue.hint != old_clue.hint
+                || new_clue.item != old_clue.item
+                || new_clue.location != old_clue.location;
+            if is_text_changed {
+                diff.changed_text.insert(old_clue.id.clone());
+            }
+        }
+        diff.added = new
+            .0
+            .iter()
+            .filter(|clue| !old_ids.contains(clue.id.as_str()))
+            .map(|clue| clue.id.clone())
+            .collect();
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changed_text.is_empty()
+            && self.changed_answer.is_empty()
+            && self.removed.is_empty()
+            && self.added.is_empty()
+    }
+}
+
+impl Clues {
+    pub fn get(&self, id: &str) -> Option<&Clue> {
+        self.0.iter().find(|clue| clue.id == id)
+    }
+}
+
+#[test]
+fn test_clue_diff() {
+    let old = Clues::mock();
+    let mut new = old.clone();
+    new.0[0].poem = "Fixed a typo".into();
+    new.0[1].code = crate::clues::answer_to_code("new answer");
+    new.0.remove(2);
+    new.0.push(Clue::mock(100, "Z"));
+
+    // Sessions can hold several copies of the same clue
+    let diff = ClueDiff::new(old.0.iter().chain(&old.0), &new);
+    assert_eq!(diff.changed_text, BTreeSet::from(["0".into()]));
+    assert_eq!(diff.changed_answer, BTreeSet::from(["1".into()]));
+    assert_eq!(diff.removed, BTreeSet::from(["2".into()]));
+    assert_eq!(diff.added, BTreeSet::from(["100".into()]));
+
+    assert!(ClueDiff::new(&old.0, &old).is_empty());
+}
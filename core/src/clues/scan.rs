@@ -0,0 +1,51 @@
+//! Answering clues by scanning a QR code printed on the item.
+//!
+//! Each clue's QR code holds a URL with a token derived from the clue's code and a
+//! secret, so teams cannot make the URL for a clue from what the server shows them.
+
+use {
+    super::Clue,
+    hmac::{Hmac, Mac},
+    sha3::Sha3_256,
+};
+
+/// Number of bytes of the HMAC used in a scan token.
+const SCAN_TOKEN_BYTES: usize = 8;
+
+/// Secret used to derive scan tokens. It must not be shared with the players.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ScanKey(Vec<u8>);
+
+impl std::fmt::Debug for ScanKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ScanKey(..)")
+    }
+}
+
+impl ScanKey {
+    pub fn new(secret: &str) -> Self {
+        Self(secret.as_bytes().to_vec())
+    }
+
+    /// The token in the URL of the clue's QR code, e.g. `/scan/{token}`.
+    pub fn token(&self, clue: &Clue) -> String {
+        let mut mac =
+            Hmac::<Sha3_256>::new_from_slice(&self.0).expect("HMAC can take a key of any size");
+        mac.update(b"scan");
+        mac.update(&clue.code);
+        let digest = mac.finalize().into_bytes();
+        hex::encode(&digest[..SCAN_TOKEN_BYTES])
+    }
+}
+
+#[test]
+fn test_scan_token() {
+    let key = ScanKey::new("secret");
+    let clue_a = Clue::mock(0, "A");
+    let clue_b = Clue::mock(1, "A");
+    let token = key.token(&clue_a);
+    assert_eq!(token.len(), 2 * SCAN_TOKEN_BYTES);
+    assert_eq!(token, key.token(&clue_a), "Tokens are stable");
+    assert_ne!(token, key.token(&clue_b));
+    assert_ne!(token, ScanKey::new("other").token(&clue_a));
+}
@@ -15,6 +15,12 @@ pub enum Status {
         kind: KnowledgeKind,
         duration: Duration,
     },
+    /// A photo was submitted and awaits the game master's approval. The time taken
+    /// is measured up to the submission.
+    Pending {
+        kind: KnowledgeKind,
+        duration: Duration,
+    },
     Declined,
 }
 
@@ -32,7 +38,9 @@ impl<'a> CurrentClueStatus<'a> {
     pub fn new(status: &'a mut Status) -> Option<Self> {
         match status {
             Status::Seen { .. } | Status::Skipped { .. } => Some(Self { inner: status }),
-            Status::Unread | Status::Solved { .. } | Status::Declined => None,
+            Status::Unread | Status::Solved { .. } | Status::Pending { .. } | Status::Declined => {
+                None
+            }
         }
     }
 
@@ -51,6 +59,14 @@ impl<'a> CurrentClueStatus<'a> {
         self.inner
     }
 
+    /// Stop the clock while the game master checks the team's photo.
+    pub fn submitted(mut self) -> &'a mut Status {
+        let (kind, time) = self.unpack();
+        let duration = time.elapsed();
+        *self.inner = Status::Pending { kind, duration };
+        self.inner
+    }
+
     pub fn hinted(&mut self) {
         self.kind().hinted();
     }
@@ -75,6 +91,11 @@ impl<'a> CurrentClueStatus<'a> {
         }
     }
 
+    /// Give up on the clue forever, e.g. when the team has no attempts left.
+    pub fn decline(self) {
+        *self.inner = Status::Declined;
+    }
+
     pub fn duration(&self) -> Duration {
         let time = match &self.inner {
             Status::Seen { time, .. } | Status::Skipped { time, .. } => time,
@@ -128,6 +149,21 @@ impl KnowledgeKind {
     }
 }
 
+/// The parts of the score for one clue.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Points {
+    /// Points for solving the clue, depending on how much help was needed.
+    pub base: i32,
+    /// Bonus points for solving the clue quickly.
+    pub time_bonus: i32,
+}
+
+impl Points {
+    pub fn total(&self) -> i32 {
+        self.base + self.time_bonus
+    }
+}
+
 impl Status {
     /// Scoring:
     /// - 300 points for solving unaided
@@ -135,9 +171,56 @@ impl Status {
     /// - 100 points for finding the item after it is revealed to you
     /// - up to 100 bonus points depending on how quickly you found the answer
     pub fn score(&self) -> i32 {
+        self.points().total()
+    }
+
+    /// The score broken down into its parts, see `Status::score`.
+    pub fn points(&self) -> Points {
         match self {
-            Self::Solved { kind, duration } => kind.score() + duration_bonus_score(duration),
-            Self::Unread | Self::Seen { .. } | Self::Skipped { .. } | Self::Declined => 0,
+            Self::Solved { kind, duration } => Points {
+                base: kind.score(),
+                time_bonus: duration_bonus_score(duration),
+            },
+            Self::Unread
+            | Self::Seen { .. }
+            | Self::Skipped { .. }
+            | Self::Pending { .. }
+            | Self::Declined => Points::default(),
+        }
+    }
+
+    /// Solve a clue whose photo was approved. Returns false unless the clue was pending.
+    pub fn approve(&mut self) -> bool {
+        let Self::Pending { kind, duration } = *self else {
+            return false;
+        };
+        *self = Self::Solved { kind, duration };
+        true
+    }
+
+    /// Send a clue whose photo was rejected back to the team, as if they had skipped it,
+    /// so the time the game master took is not counted. Returns false unless the clue
+    /// was pending.
+    pub fn reject(&mut self) -> bool {
+        let Self::Pending { kind, duration } = *self else {
+            return false;
+        };
+        let now = Instant::now();
+        *self = Self::Skipped {
+            kind,
+            time: now.checked_sub(duration).unwrap_or(now),
+        };
+        true
+    }
+
+    /// How much help the team had with the clue, unless it is unread or declined.
+    pub fn knowledge(&self) -> Option<KnowledgeKind> {
+        match self {
+            Self::Seen { kind, .. }
+            | Self::Skipped { kind, .. }
+            | Self::Solved { kind, .. }
+            | Self::Pending { kind, .. } => Some(*kind),
+            Self::Unread | Self::Declined => None,
         }
     }
 }
@@ -201,5 +284,16 @@ fn test_scoring() {
             100 + bonus
         );
         assert_eq!(Status::Declined.score(), 0);
+        assert_eq!(
+            Status::Solved {
+                kind: KnowledgeKind::WithHint,
+                duration
+            }
+            .points(),
+            Points {
+                base: 200,
+                time_bonus: bonus
+            }
+        );
     }
 }
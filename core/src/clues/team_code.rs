@@ -0,0 +1,126 @@
+//! Team-specific answer codes.
+//!
+//! In the default mode every team submits the answer written on the item it found.
+//! When team codes are enabled, the expected answer is the base answer followed by
+//! a short code that depends on both the team's session and the clue. Each team is
+//! given a printed sheet of its own codes, so an answer texted over from another
+//! team is not accepted.
+
+use {
+    crate::{
+        clues::{self, Clue},
+        session::SessionId,
+    },
+    hmac::{Hmac, Mac},
+    sha3::Sha3_256,
+};
+
+/// Number of characters in a team code.
+const TEAM_CODE_LEN: usize = 4;
+
+/// Characters used in team codes. Letters and digits which are easily confused
+/// with one another (`I`, `O`, `0`, `1`) are excluded.
+const TEAM_CODE_ALPHABET: &[u8; 32] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnswerMode {
+    /// All teams submit the same answer for a clue.
+    Shared,
+    /// Teams submit the base answer followed by their own code for the clue.
+    TeamSpecific(TeamCodeKey),
+}
+
+impl AnswerMode {
+    pub fn team_specific(secret: &str) -> Self {
+        Self::TeamSpecific(TeamCodeKey(secret.as_bytes().to_vec()))
+    }
+
+    /// Check if the submitted answer is correct for the given clue in this mode.
+    pub fn is_correct(&self, session_id: &SessionId, clue: &Clue, submitted_answer: &str) -> bool {
+        match self {
+            Self::Shared => clues::answer_to_code(submitted_answer) == clue.code,
+            Self::TeamSpecific(key) => {
+                let team_code = key.team_code(session_id, &clue.code);
+                let Some(base_answer) = strip_team_code(submitted_answer, &team_code) else {
+                    return false;
+                };
+                clues::answer_to_code(base_answer) == clue.code
+            }
+        }
+    }
+}
+
+/// Secret used to derive team codes. It must not be shared with the players.
+#[derive(Clone, PartialEq, Eq)]
+pub struct TeamCodeKey(Vec<u8>);
+
+impl std::fmt::Debug for TeamCodeKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("TeamCodeKey(..)")
+    }
+}
+
+impl TeamCodeKey {
+    /// The team code is derived from an HMAC over the session ID and clue code.
+    pub fn team_code(&self, session_id: &SessionId, clue_code: &[u8; 32]) -> String {
+        let mut mac =
+            Hmac::<Sha3_256>::new_from_slice(&self.0).expect("HMAC can take a key of any size");
+        mac.update(session_id.to_string().as_bytes());
+        mac.update(clue_code);
+        let digest = mac.finalize().into_bytes();
+        digest
+            .iter()
+            .take(TEAM_CODE_LEN)
+            .map(|b| char::from(TEAM_CODE_ALPHABET[usize::from(*b) % TEAM_CODE_ALPHABET.len()]))
+            .collect()
+    }
+}
+
+/// Remove the team code from the end of the submitted answer, returning the base answer.
+/// The code is case-insensitive and may be separated from the base answer by spaces or a dash.
+fn strip_team_code<'a>(submitted_answer: &'a str, team_code: &str) -> Option<&'a str> {
+    let trimmed = submitted_answer.trim_end();
+    let split = trimmed.len().checked_sub(team_code.len())?;
+    let suffix = trimmed.get(split..)?;
+    if !suffix.eq_ignore_ascii_case(team_code) {
+        return None;
+    }
+    let base = trimmed[..split].trim_end_matches([' ', '-']);
+    Some(base)
+}
+
+#[test]
+fn test_team_specific_answers() {
+    let clue = Clue::mock(0, "A");
+    let session_a = SessionId::new("AAAA").unwrap();
+    let session_b = SessionId::new("BBBB").unwrap();
+    let mode = AnswerMode::team_specific("secret");
+    let AnswerMode::TeamSpecific(key) = &mode else {
+        unreachable!()
+    };
+
+    let code_a = key.team_code(&session_a, &clue.code);
+    let code_b = key.team_code(&session_b, &clue.code);
+    assert_eq!(code_a.len(), TEAM_CODE_LEN);
+    assert_ne!(code_a, code_b, "Teams get different codes");
+
+    assert!(mode.is_correct(&session_a, &clue, &format!("0 {code_a}")));
+    assert!(mode.is_correct(&session_a, &clue, &format!("0-{code_a}")));
+    assert!(mode.is_correct(
+        &session_a,
+        &clue,
+        &format!("0{}", code_a.to_ascii_lowercase())
+    ));
+    assert!(
+        !mode.is_correct(&session_a, &clue, "0"),
+        "Base answer alone is not accepted"
+    );
+    assert!(
+        !mode.is_correct(&session_b, &clue, &format!("0 {code_a}")),
+        "Another team's code is not accepted"
+    );
+    assert!(!mode.is_correct(&session_a, &clue, &format!("1 {code_a}")));
+
+    assert!(AnswerMode::Shared.is_correct(&session_a, &clue, "0"));
+    assert!(!AnswerMode::Shared.is_correct(&session_a, &clue, &format!("0 {code_a}")));
+}
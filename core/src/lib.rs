@@ -1,2 +1,4 @@
+pub mod analytics;
+pub mod anomaly;
 pub mod clues;
 pub mod session;
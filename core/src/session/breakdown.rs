@@ -0,0 +1,115 @@
+//! How a team's score is made up, so teams can see why they have their score.
+
+use {
+    super::{
+        Session,
+        history::{GuessOutcome, HistoryEvent},
+    },
+    crate::clues::status::{Points, Status},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScoreBreakdown {
+    /// The clues the team has seen, in the order of its arrangement.
+    pub clues: Vec<ClueBreakdown>,
+    /// Points lost for submitting answers to other clues (zero or negative).
+    pub penalty_points: i32,
+    /// Points granted regardless of clues, e.g. for registering late.
+    pub credit_points: i32,
+    /// Points charged for the game master's help (zero or negative).
+    pub help_points: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClueBreakdown {
+    /// Position of the clue in the team's arrangement, starting from 1.
+    pub number: usize,
+    pub clue_id: String,
+    pub status: Status,
+    pub points: Points,
+    /// Number of answers to other clues submitted while this was the current clue.
+    pub other_clue_answers: usize,
+}
+
+impl ScoreBreakdown {
+    /// The same as `Session::total_score`.
+    pub fn total(&self) -> i32 {
+        self.clues.iter().fold(
+            self.penalty_points
+                .saturating_add(self.credit_points)
+                .saturating_add(self.help_points),
+            |acc, clue| acc.saturating_add(clue.points.total()),
+        )
+    }
+}
+
+impl Session {
+    pub fn score_breakdown(&self) -> ScoreBreakdown {
+        let clues = self
+            .clues
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, status))| !matches!(status, Status::Unread))
+            .map(|(i, (clue, status))| {
+                let other_clue_answers = self
+                    .history
+                    .iter()
+                    .filter(|entry| {
+                        entry.clue_code == clue.code
+                            && matches!(
+                                entry.event,
+                                HistoryEvent::Guess {
+                                    outcome: GuessOutcome::OtherClue,
+                                    ..
+                                }
+                            )
+                    })
+                    .count();
+                ClueBreakdown {
+                    number: i + 1,
+                    clue_id: clue.id.clone(),
+                    status: status.clone(),
+                    points: status.points(),
+                    other_clue_answers,
+                }
+            })
+            .collect();
+        ScoreBreakdown {
+            clues,
+            penalty_points: self.negative_points,
+            credit_points: self.credit_points,
+            help_points: self.help_points(),
+        }
+    }
+}
+
+#[test]
+fn test_score_breakdown() {
+    use crate::{
+        clues::{Clues, status::KnowledgeKind, team_code::AnswerMode},
+        session::SessionId,
+    };
+
+    let clues = Clues::mock();
+    let mut session = Session::new(SessionId::new("TEST").unwrap(), clues.clone());
+    let mode = AnswerMode::Shared;
+    session.grant_credit(50);
+    session.current_clue();
+    session.hint_current_clue(None);
+    session.try_solve(&clues.0[3].id, &mode, None);
+    session.try_solve(&clues.0[0].id, &mode, None);
+    session.current_clue();
+
+    let breakdown = session.score_breakdown();
+    assert_eq!(breakdown.total(), session.total_score());
+    assert_eq!(breakdown.clues.len(), 2, "Unread clues are left out");
+    assert_eq!(breakdown.penalty_points, -super::OTHER_CLUE_PENALTY);
+    assert_eq!(breakdown.credit_points, 50);
+
+    let first = &breakdown.clues[0];
+    assert_eq!(first.number, 1);
+    assert_eq!(first.points.base, KnowledgeKind::WithHint.score());
+    assert!(first.points.time_bonus > 0);
+    assert_eq!(first.other_clue_answers, 1);
+    assert_eq!(breakdown.clues[1].points.total(), 0);
+}
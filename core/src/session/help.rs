@@ -0,0 +1,108 @@
+//! Free-text requests for help from the game master, and their replies. Both are
+//! kept in the session history, so nothing else needs to be persisted.
+
+use super::{
+    Session,
+    history::{HistoryEntry, HistoryEvent},
+};
+
+impl Session {
+    /// Ask the game master for help with the current clue. Returns false if there
+    /// is no current clue.
+    pub fn request_help(&mut self, message: &str, member: Option<&str>) -> bool {
+        let Some((clue, _)) = self.inner_current_clue() else {
+            return false;
+        };
+        let clue_code = clue.code;
+        let event = HistoryEvent::HelpRequest {
+            message: message.into(),
+        };
+        self.history
+            .push(HistoryEntry::now(clue_code, member, event));
+        true
+    }
+
+    /// Reply to the team's requests for help with the clue with the given code,
+    /// charging them `points` (which may be zero). Returns false if the team does not
+    /// have the clue.
+    pub fn reply_to_help(&mut self, clue_code: &[u8; 32], message: &str, points: i32) -> bool {
+        if !self.clues.iter().any(|(clue, _)| &clue.code == clue_code) {
+            return false;
+        }
+        let event = HistoryEvent::HelpReply {
+            message: message.into(),
+            points,
+        };
+        self.history
+            .push(HistoryEntry::now(*clue_code, None, event));
+        true
+    }
+
+    /// Points charged for the game master's help (zero or negative).
+    pub fn help_points(&self) -> i32 {
+        self.history
+            .iter()
+            .fold(0, |acc: i32, entry| match &entry.event {
+                HistoryEvent::HelpReply { points, .. } => acc.saturating_sub(*points),
+                _ => acc,
+            })
+    }
+
+    /// The game master's replies about the clue with the given code, oldest first.
+    pub fn help_replies(&self, clue_code: &[u8; 32]) -> Vec<&str> {
+        self.history
+            .iter()
+            .filter(|entry| &entry.clue_code == clue_code)
+            .filter_map(|entry| match &entry.event {
+                HistoryEvent::HelpReply { message, .. } => Some(message.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Requests for help which the game master has yet to reply to, oldest first.
+    pub fn pending_help_requests(&self) -> Vec<&HistoryEntry> {
+        let mut pending: Vec<&HistoryEntry> = Vec::new();
+        for entry in &self.history {
+            match &entry.event {
+                HistoryEvent::HelpRequest { .. } => pending.push(entry),
+                HistoryEvent::HelpReply { .. } => {
+                    pending.retain(|request| request.clue_code != entry.clue_code);
+                }
+                _ => (),
+            }
+        }
+        pending
+    }
+}
+
+#[test]
+fn test_help() {
+    use crate::{
+        clues::{Clues, team_code::AnswerMode},
+        session::SessionId,
+    };
+
+    let clues = Clues::mock();
+    let mut session = Session::new(SessionId::new("TEST").unwrap(), clues.clone());
+    let first = clues.0[0].code;
+    assert!(session.request_help("Where is the shed?", Some("Alice")));
+    assert!(session.request_help("Hello?", None));
+    assert_eq!(session.pending_help_requests().len(), 2);
+    assert!(session.current_clue().unwrap().awaiting_help);
+
+    assert!(session.reply_to_help(&first, "Behind the house", 50));
+    assert!(session.pending_help_requests().is_empty());
+    assert_eq!(session.help_points(), -50);
+    assert_eq!(session.help_replies(&first), vec!["Behind the house"]);
+    let view = session.current_clue().unwrap();
+    assert_eq!(view.help_replies, vec![String::from("Behind the house")]);
+    assert!(!view.awaiting_help);
+
+    session.try_solve(&clues.0[0].id, &AnswerMode::Shared, None);
+    assert_eq!(session.total_score(), 400 - 50, "Help is charged");
+    assert!(
+        !session.reply_to_help(&[0; 32], "Nonsense", 0),
+        "Replies must be about one of the team's clues"
+    );
+}
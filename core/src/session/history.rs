@@ -0,0 +1,106 @@
+//! A record of the actions a team took during its session.
+
+use {
+    serde::{Deserialize, Serialize},
+    std::time::SystemTime,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub time: SystemTime,
+    /// Code of the team's current clue at the time of the event.
+    pub clue_code: [u8; 32],
+    /// Display name of the team member who performed the action, if known.
+    #[serde(default)]
+    pub member: Option<String>,
+    pub event: HistoryEvent,
+}
+
+impl HistoryEntry {
+    pub fn now(clue_code: [u8; 32], member: Option<&str>, event: HistoryEvent) -> Self {
+        Self {
+            time: SystemTime::now(),
+            clue_code,
+            member: member.map(String::from),
+            event,
+        }
+    }
+}
+
+/// Longer guesses are cut short before they are recorded, so that a team cannot grow
+/// the persisted state without limit.
+pub const MAX_GUESS_BYTES: usize = 200;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HistoryEvent {
+    Guess {
+        answer: String,
+        outcome: GuessOutcome,
+    },
+    Hint,
+    Reveal,
+    /// Skipping a clue which was already skipped declines it.
+    Skip,
+    /// A free-text request for help from the game master.
+    HelpRequest {
+        message: String,
+    },
+    /// The game master's reply to the team's requests for help with this clue.
+    HelpReply {
+        message: String,
+        /// Points charged for the help.
+        points: i32,
+    },
+    /// A photo answering the clue was uploaded, and saved with the given file name.
+    PhotoSubmitted {
+        file: String,
+    },
+    /// The game master approved (solving the clue) or rejected the team's photo.
+    PhotoReviewed {
+        approved: bool,
+    },
+}
+
+impl HistoryEvent {
+    /// A guess of `answer`, keeping at most `MAX_GUESS_BYTES` of it.
+    pub fn guess(answer: &str, outcome: GuessOutcome) -> Self {
+        let mut end = answer.len().min(MAX_GUESS_BYTES);
+        while !answer.is_char_boundary(end) {
+            end -= 1;
+        }
+        Self::Guess {
+            answer: answer[..end].into(),
+            outcome,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GuessOutcome {
+    /// The answer solved the current clue.
+    Correct,
+    /// The answer belongs to a different clue.
+    OtherClue,
+    /// The answer does not match any clue.
+    Wrong,
+}
+
+#[test]
+fn test_guess_is_cut_short() {
+    assert_eq!(
+        HistoryEvent::guess("pear", GuessOutcome::Wrong),
+        HistoryEvent::Guess {
+            answer: String::from("pear"),
+            outcome: GuessOutcome::Wrong
+        }
+    );
+    // Two bytes per character, so this stops short of a character boundary.
+    let long = format!("a{}", "é".repeat(MAX_GUESS_BYTES));
+    assert_eq!(
+        HistoryEvent::guess(&long, GuessOutcome::Wrong),
+        HistoryEvent::Guess {
+            answer: format!("a{}", "é".repeat(MAX_GUESS_BYTES / 2 - 1)),
+            outcome: GuessOutcome::Wrong
+        }
+    );
+}
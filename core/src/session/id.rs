@@ -1,38 +1,294 @@
-use {rand::Rng, std::fmt};
+use {
+    rand::Rng,
+    serde::{Deserialize, Serialize, Serializer},
+    std::fmt,
+};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct GenericSessionId<const N: usize>([u8; N]);
+/// The longest session ID which can be represented, including any check character.
+pub const MAX_SESSION_ID_LEN: usize = 16;
 
-impl<const N: usize> GenericSessionId<N> {
+/// Number of random IDs to try before concluding that (almost) all IDs are taken.
+const MAX_GENERATE_ATTEMPTS: usize = 1_000;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SessionId {
+    bytes: [u8; MAX_SESSION_ID_LEN],
+    len: u8,
+}
+
+impl SessionId {
+    /// Create a session ID from any code of ASCII letters and digits, without checking it
+    /// against a `SessionIdFormat`. This is used for IDs which were already issued.
     pub fn new(code: &str) -> Option<Self> {
         if !Self::validate_code(code) {
             return None;
         }
 
-        Some(Self(
-            code.to_ascii_uppercase().as_bytes().try_into().unwrap(),
-        ))
+        let mut bytes = [0u8; MAX_SESSION_ID_LEN];
+        bytes[..code.len()].copy_from_slice(code.to_ascii_uppercase().as_bytes());
+        Some(Self {
+            bytes,
+            len: code.len() as u8,
+        })
     }
 
-    pub fn random() -> Self {
-        let mut buf = [0u8; N];
-        let mut rng = rand::rng();
-        for x in buf.iter_mut() {
-            *x = rng.random_range(b'A'..=b'Z');
-        }
-        Self(buf)
+    pub fn as_str(&self) -> &str {
+        str::from_utf8(&self.bytes[..usize::from(self.len)]).expect("Session IDs are always ASCII")
     }
 
     fn validate_code(code: &str) -> bool {
-        code.is_ascii() && code.len() == N
+        !code.is_empty()
+            && code.len() <= MAX_SESSION_ID_LEN
+            && code.bytes().all(|b| b.is_ascii_alphanumeric())
+    }
+}
+
+impl fmt::Display for SessionId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
     }
 }
 
-impl<const N: usize> fmt::Display for GenericSessionId<N> {
+impl fmt::Debug for SessionId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let s = str::from_utf8(&self.0).map_err(|_| fmt::Error)?;
-        write!(f, "{s}")
+        write!(f, "SessionId({})", self.as_str())
+    }
+}
+
+impl Serialize for SessionId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
     }
 }
 
-pub type SessionId = GenericSessionId<4>;
+/// The shape of newly issued session IDs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionIdFormat {
+    /// Number of random characters in an ID (not including the check character).
+    pub length: usize,
+    /// Characters IDs are drawn from. The default leaves out letters which are
+    /// easily confused with digits (`I` and `O`).
+    pub alphabet: String,
+    /// If true, a check character is appended so that typos can be detected.
+    pub check_character: bool,
+}
+
+impl Default for SessionIdFormat {
+    fn default() -> Self {
+        Self {
+            length: 4,
+            alphabet: String::from("ABCDEFGHJKLMNPQRSTUVWXYZ"),
+            check_character: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionIdError {
+    WrongLength { expected: usize },
+    InvalidCharacter(char),
+    Typo,
+}
+
+impl fmt::Display for SessionIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WrongLength { expected } => {
+                write!(f, "Session IDs are {expected} characters long")
+            }
+            Self::InvalidCharacter(c) => write!(f, "Session IDs never contain '{c}'"),
+            Self::Typo => f.write_str("That session ID has a typo in it, check it and try again"),
+        }
+    }
+}
+
+impl std::error::Error for SessionIdError {}
+
+impl SessionIdFormat {
+    /// Check the format can be used to generate session IDs.
+    pub fn validate(&self) -> Result<(), String> {
+        let total_len = self.total_len();
+        if self.length == 0 || total_len > MAX_SESSION_ID_LEN {
+            return Err(format!(
+                "Session ID length must be between 1 and {MAX_SESSION_ID_LEN} (including any check character)"
+            ));
+        }
+        if self.alphabet.len() < 2 {
+            return Err("Session ID alphabet must have at least two characters".into());
+        }
+        let upper = self.alphabet.to_ascii_uppercase();
+        if !upper.bytes().all(|b| b.is_ascii_alphanumeric()) {
+            return Err("Session ID alphabet must only contain ASCII letters and digits".into());
+        }
+        let mut sorted: Vec<u8> = upper.into_bytes();
+        sorted.sort_unstable();
+        sorted.dedup();
+        if sorted.len() != self.alphabet.len() {
+            return Err("Session ID alphabet must not repeat characters".into());
+        }
+        Ok(())
+    }
+
+    /// Generate a random session ID for which `is_taken` returns false.
+    /// Returns `None` if no free ID could be found.
+    pub fn generate<F>(&self, is_taken: F) -> Option<SessionId>
+    where
+        F: Fn(&SessionId) -> bool,
+    {
+        let alphabet = self.alphabet_bytes();
+        let mut rng = rand::rng();
+        for _ in 0..MAX_GENERATE_ATTEMPTS {
+            let mut code: Vec<u8> = (0..self.length)
+                .map(|_| alphabet[rng.random_range(0..alphabet.len())])
+                .collect();
+            if self.check_character {
+                code.push(self.compute_check_character(&code));
+            }
+            let id = SessionId::new(str::from_utf8(&code).ok()?)?;
+            if !is_taken(&id) {
+                return Some(id);
+            }
+        }
+        None
+    }
+
+    /// Parse a session ID typed in by a player. Spaces and dashes are ignored, and
+    /// letters may be in either case.
+    pub fn parse(&self, input: &str) -> Result<SessionId, SessionIdError> {
+        let code: Vec<u8> = input
+            .bytes()
+            .filter(|b| !matches!(b, b' ' | b'-'))
+            .map(|b| b.to_ascii_uppercase())
+            .collect();
+        let alphabet = self.alphabet_bytes();
+        if let Some(c) = code.iter().find(|b| !alphabet.contains(b)) {
+            return Err(SessionIdError::InvalidCharacter(char::from(*c)));
+        }
+        if code.len() != self.total_len() {
+            return Err(SessionIdError::WrongLength {
+                expected: self.total_len(),
+            });
+        }
+        if self.check_character {
+            let (body, check) = code.split_at(self.length);
+            if check[0] != self.compute_check_character(body) {
+                return Err(SessionIdError::Typo);
+            }
+        }
+        let code = str::from_utf8(&code).map_err(|_| SessionIdError::Typo)?;
+        SessionId::new(code).ok_or(SessionIdError::Typo)
+    }
+
+    /// Find the session ID typed in by a player among the IDs for which `is_issued` returns
+    /// true. IDs issued before the format changed (e.g. with letters no longer in the
+    /// alphabet) are found as typed, so the format only reports typos in unknown IDs.
+    pub fn find<F>(&self, input: &str, is_issued: F) -> Result<SessionId, SessionIdError>
+    where
+        F: Fn(&SessionId) -> bool,
+    {
+        let code: String = input.chars().filter(|c| !matches!(c, ' ' | '-')).collect();
+        match SessionId::new(&code) {
+            Some(id) if is_issued(&id) => Ok(id),
+            _ => self.parse(input),
+        }
+    }
+
+    fn total_len(&self) -> usize {
+        self.length + usize::from(self.check_character)
+    }
+
+    fn alphabet_bytes(&self) -> Vec<u8> {
+        self.alphabet.to_ascii_uppercase().into_bytes()
+    }
+
+    /// Luhn mod N check character, which detects any single mistyped
+    /// character and most transpositions of adjacent characters.
+    fn compute_check_character(&self, code: &[u8]) -> u8 {
+        let alphabet = self.alphabet_bytes();
+        let n = alphabet.len();
+        let mut factor = 2;
+        let mut sum = 0;
+        for c in code.iter().rev() {
+            let code_point = alphabet.iter().position(|a| a == c).unwrap_or_default();
+            let addend = factor * code_point;
+            sum += addend / n + addend % n;
+            factor = if factor == 2 { 1 } else { 2 };
+        }
+        alphabet[(n - sum % n) % n]
+    }
+}
+
+#[test]
+fn test_session_id_format() {
+    let format = SessionIdFormat {
+        length: 5,
+        alphabet: "ABCDEFGHJKLMNPQRSTUVWXYZ".into(),
+        check_character: true,
+    };
+    assert_eq!(format.validate(), Ok(()));
+
+    let taken = format.generate(|_| false).unwrap();
+    assert_eq!(taken.as_str().len(), 6);
+    let id = format.generate(|id| id == &taken).unwrap();
+    assert_ne!(id, taken, "Generated IDs avoid taken IDs");
+    assert!(
+        format.generate(|_| true).is_none(),
+        "Generation gives up if all IDs are taken"
+    );
+
+    let code = id.as_str();
+    assert_eq!(format.parse(code), Ok(id));
+    assert_eq!(format.parse(&code.to_ascii_lowercase()), Ok(id));
+    assert_eq!(
+        format.parse(&format!("{}-{}", &code[..3], &code[3..])),
+        Ok(id)
+    );
+    assert_eq!(
+        format.parse(&code[1..]),
+        Err(SessionIdError::WrongLength { expected: 6 })
+    );
+    assert_eq!(
+        format.parse(&format!("O{}", &code[1..])),
+        Err(SessionIdError::InvalidCharacter('O'))
+    );
+
+    // Every single character typo is detected
+    for i in 0..code.len() {
+        for c in format.alphabet.chars() {
+            let mut typo: Vec<char> = code.chars().collect();
+            if typo[i] == c {
+                continue;
+            }
+            typo[i] = c;
+            let typo: String = typo.into_iter().collect();
+            assert_eq!(format.parse(&typo), Err(SessionIdError::Typo), "{typo}");
+        }
+    }
+
+    let invalid = SessionIdFormat {
+        alphabet: "AAB".into(),
+        ..SessionIdFormat::default()
+    };
+    assert!(invalid.validate().is_err());
+}
+
+#[test]
+fn test_find_issued_session_id() {
+    let format = SessionIdFormat {
+        length: 4,
+        alphabet: "ABCDEFGHJKLMNPQRSTUVWXYZ".into(),
+        check_character: false,
+    };
+    let legacy = SessionId::new("IOAB").unwrap();
+    let is_issued = |id: &SessionId| id == &legacy;
+    assert_eq!(format.find("ioab", is_issued), Ok(legacy));
+    assert_eq!(format.find("IO-AB", is_issued), Ok(legacy));
+    assert_eq!(
+        format.find("IOAC", is_issued),
+        Err(SessionIdError::InvalidCharacter('I')),
+        "Unknown IDs are checked against the format"
+    );
+    let current = SessionId::new("ABCD").unwrap();
+    assert_eq!(format.find("abcd", is_issued), Ok(current));
+}
@@ -1,34 +1,53 @@
 use {
-    self::serialization::SerializableSession,
+    self::{
+        history::{GuessOutcome, HistoryEntry, HistoryEvent},
+        serialization::SerializableSession,
+    },
     crate::clues::{
-        self, Clue, ClueView, Clues,
+        Clue, ClueView, Clues,
+        kind::ClueKind,
+        scan::ScanKey,
         status::{CurrentClueStatus, KnowledgeKind, Status},
+        team_code::{AnswerMode, TeamCodeKey},
     },
     std::time::{Duration, Instant},
 };
 
+pub mod breakdown;
+mod help;
+pub mod history;
 mod id;
+pub mod photo;
 mod serialization;
+pub mod standing;
+
+pub use id::{SessionId, SessionIdError, SessionIdFormat};
 
-pub use id::SessionId;
+/// Points lost for submitting the answer to a different clue.
+pub const OTHER_CLUE_PENALTY: i32 = 100;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq)]
 pub struct Session {
     pub id: SessionId,
     clues: Vec<(Clue, Status)>,
     negative_points: i32,
+    /// Points granted to the team regardless of its clues, e.g. for joining late.
+    credit_points: i32,
+    history: Vec<HistoryEntry>,
 }
 
 impl Session {
-    pub fn new(clues: Clues) -> Self {
+    pub fn new(id: SessionId, clues: Clues) -> Self {
         Self {
-            id: SessionId::random(),
+            id,
             clues: clues
                 .0
                 .into_iter()
                 .map(|clue| (clue, Status::Unread))
                 .collect(),
             negative_points: 0,
+            credit_points: 0,
+            history: Vec::new(),
         }
     }
 
@@ -42,12 +61,75 @@ impl Session {
         Ok(serializable.into())
     }
 
+    /// All clues in this session's arrangement together with their current status.
+    pub fn clues(&self) -> impl Iterator<Item = (&Clue, &Status)> {
+        self.clues.iter().map(|(clue, status)| (clue, status))
+    }
+
+    pub fn history(&self) -> &[HistoryEntry] {
+        &self.history
+    }
+
     pub fn total_score(&self) -> i32 {
-        self.clues
+        let extra_points = self
+            .negative_points
+            .saturating_add(self.credit_points)
+            .saturating_add(self.help_points());
+        self.clues.iter().fold(extra_points, |acc, (_, status)| {
+            acc.saturating_add(status.score())
+        })
+    }
+
+    /// Points lost for submitting answers to other clues (zero or negative).
+    pub fn penalty_points(&self) -> i32 {
+        self.negative_points
+    }
+
+    pub fn credit_points(&self) -> i32 {
+        self.credit_points
+    }
+
+    pub fn grant_credit(&mut self, points: i32) {
+        self.credit_points = self.credit_points.saturating_add(points);
+    }
+
+    /// Locations of the clues this team has yet to solve, in the order it will visit them.
+    pub fn upcoming_locations(&self) -> Vec<&str> {
+        let pending = self
+            .clues
             .iter()
-            .fold(self.negative_points, |acc, (_, status)| {
-                acc.saturating_add(status.score())
-            })
+            .filter(|(_, status)| matches!(status, Status::Seen { .. } | Status::Unread));
+        let skipped = self
+            .clues
+            .iter()
+            .filter(|(_, status)| matches!(status, Status::Skipped { .. }));
+        pending
+            .chain(skipped)
+            .map(|(clue, _)| clue.location.as_str())
+            .collect()
+    }
+
+    /// The location of the clue the team is working on, or `None` if it has yet to
+    /// open its next clue (or has finished). Unlike `current_clue`, this does not
+    /// open the next clue.
+    pub fn current_location(&self) -> Option<&str> {
+        let seen = self
+            .clues
+            .iter()
+            .find(|(_, status)| matches!(status, Status::Seen { .. }));
+        let has_unread = self
+            .clues
+            .iter()
+            .any(|(_, status)| matches!(status, Status::Unread));
+        let current = match seen {
+            Some(current) => Some(current),
+            None if has_unread => None,
+            None => self
+                .clues
+                .iter()
+                .find(|(_, status)| matches!(status, Status::Skipped { .. })),
+        };
+        current.map(|(clue, _)| clue.location.as_str())
     }
 
     pub fn current_clue_duration(&mut self) -> Option<Duration> {
@@ -57,58 +139,206 @@ impl Session {
 
     pub fn current_clue(&mut self) -> Option<ClueView> {
         let (clue, status) = self.inner_current_clue()?;
+        let clue = clue.clone();
         let is_previously_skipped = status.is_skipped();
         let duration = status.duration();
+        let knowledge = status.get_knowledge_kind();
+        let awaiting_help = self
+            .pending_help_requests()
+            .iter()
+            .any(|request| request.clue_code == clue.code);
         let view = ClueView {
-            clue: clue.clone(),
-            knowledge: status.get_knowledge_kind(),
+            number: self.clue_number(&clue.code),
+            help_replies: self
+                .help_replies(&clue.code)
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            awaiting_help,
+            attempts_left: clue
+                .kind
+                .max_attempts()
+                .map(|max_attempts| self.attempts_left(&clue.code, max_attempts)),
+            clue,
+            knowledge,
             is_previously_skipped,
             duration,
         };
         Some(view)
     }
 
-    pub fn try_solve(&mut self, submitted_answer: &str) -> Option<i32> {
-        let submitted_code = clues::answer_to_code(submitted_answer);
+    /// The team codes for each clue, in the order of this session's arrangement.
+    pub fn team_codes(&self, key: &TeamCodeKey) -> Vec<String> {
+        self.clues
+            .iter()
+            .map(|(clue, _)| key.team_code(&self.id, &clue.code))
+            .collect()
+    }
+
+    /// Replace this session's clues with the clues of the same ID from a reloaded
+    /// clue file, keeping their status. If a clue's answer changed, its history is
+    /// updated to refer to the new answer.
+    pub fn update_clues(&mut self, new_clues: &Clues) {
+        for (clue, _) in &mut self.clues {
+            let Some(new_clue) = new_clues.get(&clue.id) else {
+                continue;
+            };
+            if new_clue.code != clue.code {
+                for entry in &mut self.history {
+                    if entry.clue_code == clue.code {
+                        entry.clue_code = new_clue.code;
+                    }
+                }
+            }
+            *clue = new_clue.clone();
+        }
+    }
+
+    pub fn try_solve(
+        &mut self,
+        submitted_answer: &str,
+        mode: &AnswerMode,
+        member: Option<&str>,
+    ) -> Option<i32> {
+        let id = self.id;
         let (clue, status) = self.inner_current_clue()?;
+        let clue_code = clue.code;
+        let max_attempts = clue.kind.max_attempts();
 
-        if clue.code == submitted_code {
+        // Only typed answers can be the answer to some other clue. Picking an option
+        // or entering a number should not be penalised because another clue shares it.
+        let is_text = clue.kind == ClueKind::Text;
+        let (outcome, points) = if clue.is_correct(mode, &id, submitted_answer) {
             // They got it right!
             let status = status.solved();
-            return Some(status.score());
+            (GuessOutcome::Correct, Some(status.score()))
+        } else if is_text
+            && self.clues.iter().any(|(clue, _)| {
+                clue.kind == ClueKind::Text && mode.is_correct(&id, clue, submitted_answer)
+            })
+        {
+            // The answer is not right, but it matches some other clue.
+            self.negative_points = self.negative_points.saturating_sub(OTHER_CLUE_PENALTY);
+            (GuessOutcome::OtherClue, Some(-OTHER_CLUE_PENALTY))
+        } else {
+            (GuessOutcome::Wrong, None)
+        };
+
+        let event = HistoryEvent::guess(submitted_answer, outcome);
+        self.history
+            .push(HistoryEntry::now(clue_code, member, event));
+        // Only a wrong answer can use up the last attempt. After a correct answer the next
+        // clue is left unread, so its clock starts when the team opens it.
+        let is_out_of_attempts = outcome != GuessOutcome::Correct
+            && max_attempts.is_some_and(|max| self.attempts_left(&clue_code, max) == 0);
+        if is_out_of_attempts {
+            let status = self
+                .clues
+                .iter_mut()
+                .find(|(clue, _)| clue.code == clue_code)
+                .and_then(|(_, status)| CurrentClueStatus::new(status));
+            if let Some(status) = status {
+                status.decline();
+            }
         }
+        points
+    }
 
-        // The answer is not right, check if it matches some other clue
-        let matches_other_clue = self
-            .clues
+    /// How many attempts the team has left at the clue with code `clue_code`, which
+    /// allows `max_attempts`.
+    fn attempts_left(&self, clue_code: &[u8; 32], max_attempts: u32) -> u32 {
+        let wrong_guesses = self
+            .history
             .iter()
-            .any(|(clue, _)| clue.code == submitted_code);
-        if matches_other_clue {
-            // -100 points for submitting the answer for a different clue.
-            self.negative_points = self.negative_points.saturating_sub(100);
-            return Some(-100);
+            .filter(|entry| &entry.clue_code == clue_code)
+            .filter(|entry| {
+                matches!(
+                    entry.event,
+                    HistoryEvent::Guess {
+                        outcome: GuessOutcome::Wrong | GuessOutcome::OtherClue,
+                        ..
+                    }
+                )
+            })
+            .count();
+        max_attempts.saturating_sub(wrong_guesses.try_into().unwrap_or(u32::MAX))
+    }
+
+    /// Like `try_solve`, but with the token from a clue's QR code instead of a typed
+    /// answer. Only clues with typed answers can be solved by scanning. Scanning the
+    /// code of a clue which is already solved changes nothing and returns `None`.
+    pub fn try_solve_scanned(
+        &mut self,
+        token: &str,
+        key: &ScanKey,
+        member: Option<&str>,
+    ) -> Option<i32> {
+        if self.has_solved_scanned(token, key) {
+            return None;
         }
+        let (clue, status) = self.inner_current_clue()?;
+        let clue_code = clue.code;
+
+        let is_scanned = |clue: &Clue| clue.kind == ClueKind::Text && key.token(clue) == token;
+        let (outcome, points) = if is_scanned(clue) {
+            let status = status.solved();
+            (GuessOutcome::Correct, Some(status.score()))
+        } else if self.clues.iter().any(|(clue, _)| is_scanned(clue)) {
+            self.negative_points = self.negative_points.saturating_sub(OTHER_CLUE_PENALTY);
+            (GuessOutcome::OtherClue, Some(-OTHER_CLUE_PENALTY))
+        } else {
+            (GuessOutcome::Wrong, None)
+        };
 
-        None
+        let event = HistoryEvent::guess(&format!("QR code {token}"), outcome);
+        self.history
+            .push(HistoryEntry::now(clue_code, member, event));
+        points
     }
 
-    pub fn skip_current_clue(&mut self) {
-        let Some((_, status)) = self.inner_current_clue() else {
+    /// Whether `token` is from the QR code of a clue which the team has already solved,
+    /// or sent a photo for.
+    pub fn has_solved_scanned(&self, token: &str, key: &ScanKey) -> bool {
+        self.clues.iter().any(|(clue, status)| {
+            matches!(status, Status::Solved { .. } | Status::Pending { .. })
+                && clue.kind == ClueKind::Text
+                && key.token(clue) == token
+        })
+    }
+
+    pub fn skip_current_clue(&mut self, member: Option<&str>) {
+        let Some((clue, status)) = self.inner_current_clue() else {
             return;
         };
+        let clue_code = clue.code;
         status.skip();
+        self.history
+            .push(HistoryEntry::now(clue_code, member, HistoryEvent::Skip));
     }
 
-    pub fn hint_current_clue(&mut self) -> Option<String> {
+    pub fn hint_current_clue(&mut self, member: Option<&str>) -> Option<String> {
         let (clue, mut status) = self.inner_current_clue()?;
         status.hinted();
-        Some(clue.hint.clone())
+        let (clue_code, hint) = (clue.code, clue.hint.clone());
+        self.history
+            .push(HistoryEntry::now(clue_code, member, HistoryEvent::Hint));
+        Some(hint)
     }
 
-    pub fn reveal_current_item(&mut self) -> Option<String> {
+    pub fn reveal_current_item(&mut self, member: Option<&str>) -> Option<String> {
         let (clue, mut status) = self.inner_current_clue()?;
         status.revealed();
-        Some(clue.item.clone())
+        let (clue_code, item) = (clue.code, clue.item.clone());
+        self.history
+            .push(HistoryEntry::now(clue_code, member, HistoryEvent::Reveal));
+        Some(item)
+    }
+
+    fn clue_number(&self, code: &[u8; 32]) -> usize {
+        self.clues
+            .iter()
+            .position(|(clue, _)| &clue.code == code)
+            .map_or(0, |index| index + 1)
     }
 
     fn inner_current_clue<'a>(&'a mut self) -> Option<(&'a mut Clue, CurrentClueStatus<'a>)> {
@@ -145,7 +375,8 @@ impl Session {
 fn test_session() {
     let answers: Vec<String> = (0..14).map(|x| x.to_string()).collect();
     let clues = Clues::mock();
-    let mut session = Session::new(clues.clone());
+    let mut session = Session::new(SessionId::new("TEST").unwrap(), clues.clone());
+    let mode = AnswerMode::Shared;
 
     // `current_clue` is idempotent (except for the duration)
     let clue1 = session.current_clue().unwrap();
@@ -155,12 +386,12 @@ fn test_session() {
     assert_eq!(clue1.is_previously_skipped, clue2.is_previously_skipped);
 
     // We can solve the current clue
-    let points = session.try_solve(&answers[0]).unwrap();
+    let points = session.try_solve(&answers[0], &mode, None).unwrap();
     assert!(points > 300, "We solved unaided");
 
     // We get a penalty for submitting an answer to a different clue
     let clue = session.current_clue();
-    let points = session.try_solve(&answers[7]).unwrap();
+    let points = session.try_solve(&answers[7], &mode, None).unwrap();
     assert_eq!(points, -100, "Wrong submit penalty");
 
     // We are still on the same clue since we did not solve or skip
@@ -170,46 +401,52 @@ fn test_session() {
     );
 
     // Submitting a completely wrong answer does not change the state
-    assert!(session.try_solve("Hello, world!").is_none());
+    assert!(session.try_solve("Hello, world!", &mode, None).is_none());
     assert_eq!(
         clue.as_ref().unwrap().clue,
         session.current_clue().as_ref().unwrap().clue
     );
 
     // We can ask for a hint
-    assert_eq!(session.hint_current_clue(), Some(clues.0[1].hint.clone()));
+    assert_eq!(
+        session.hint_current_clue(None),
+        Some(clues.0[1].hint.clone())
+    );
 
     // Solving after the hint is worth less points
-    let points = session.try_solve(&answers[1]).unwrap();
+    let points = session.try_solve(&answers[1], &mode, None).unwrap();
     assert!(points > 200, "We solved with hint");
 
     // We can reveal the item
     session.current_clue();
-    assert_eq!(session.reveal_current_item(), Some(clues.0[2].item.clone()));
+    assert_eq!(
+        session.reveal_current_item(None),
+        Some(clues.0[2].item.clone())
+    );
 
     // Solving with item revealed is worth less points
-    let points = session.try_solve(&answers[2]).unwrap();
+    let points = session.try_solve(&answers[2], &mode, None).unwrap();
     assert!(points > 100, "We solved knowing the item");
 
     // We can skip a clue
     session.current_clue();
-    session.skip_current_clue();
+    session.skip_current_clue(None);
 
     // And solve the next one
     session.current_clue();
-    let points = session.try_solve(&answers[4]).unwrap();
+    let points = session.try_solve(&answers[4], &mode, None).unwrap();
     assert!(points > 300, "We solved unaided");
 
     // We can skip more clues
     for _ in 0..3 {
         session.current_clue();
-        session.skip_current_clue();
+        session.skip_current_clue(None);
     }
 
     // And solve the rest
     for a in &answers[8..] {
         session.current_clue();
-        let points = session.try_solve(a).unwrap();
+        let points = session.try_solve(a, &mode, None).unwrap();
         assert!(points > 300, "We solved unaided");
     }
 
@@ -220,22 +457,28 @@ fn test_session() {
     assert!(clue_view.is_previously_skipped);
 
     // If we skip a clue a second time then it is declined
-    session.skip_current_clue();
+    session.skip_current_clue(None);
     assert_eq!(session.clues[3].1, Status::Declined);
 
     // We can still ask for hints on skipped clues
     session.current_clue();
-    assert_eq!(session.hint_current_clue(), Some(clues.0[5].hint.clone()));
-    let points = session.try_solve(&answers[5]).unwrap();
+    assert_eq!(
+        session.hint_current_clue(None),
+        Some(clues.0[5].hint.clone())
+    );
+    let points = session.try_solve(&answers[5], &mode, None).unwrap();
     assert!(points > 200, "We solved with hint");
 
     session.current_clue();
-    assert_eq!(session.reveal_current_item(), Some(clues.0[6].item.clone()));
-    let points = session.try_solve(&answers[6]).unwrap();
+    assert_eq!(
+        session.reveal_current_item(None),
+        Some(clues.0[6].item.clone())
+    );
+    let points = session.try_solve(&answers[6], &mode, None).unwrap();
     assert!(points > 100, "We solved knowing the item");
 
     session.current_clue();
-    session.skip_current_clue();
+    session.skip_current_clue(None);
 
     assert!(
         session.current_clue().is_none(),
@@ -247,4 +490,162 @@ fn test_session() {
     // + 2 Solved knowing the item (and full bonus)
     // - 1 penalty for future guess
     assert_eq!(session.total_score(), 8 * 400 + 2 * 300 + 2 * 200 - 100);
+
+    // Every guess is recorded in the history
+    let outcomes: Vec<GuessOutcome> = session
+        .history()
+        .iter()
+        .filter_map(|entry| match &entry.event {
+            HistoryEvent::Guess { outcome, .. } => Some(*outcome),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(outcomes.len(), 14);
+    assert_eq!(outcomes[1], GuessOutcome::OtherClue);
+    assert_eq!(outcomes[2], GuessOutcome::Wrong);
+    assert_eq!(
+        outcomes
+            .iter()
+            .filter(|o| matches!(o, GuessOutcome::Correct))
+            .count(),
+        12
+    );
+    let skips = session
+        .history()
+        .iter()
+        .filter(|entry| entry.event == HistoryEvent::Skip)
+        .count();
+    assert_eq!(skips, 6);
+}
+
+#[test]
+fn test_update_clues() {
+    let clues = Clues::mock();
+    let mut session = Session::new(SessionId::new("TEST").unwrap(), clues.clone());
+    let mode = AnswerMode::Shared;
+    session.current_clue();
+    assert!(session.try_solve("wrong", &mode, None).is_none());
+
+    let mut new_clues = clues.clone();
+    new_clues.0[0].poem = "Fixed a typo".into();
+    new_clues.0[0].code = crate::clues::answer_to_code("new answer");
+    session.update_clues(&new_clues);
+
+    let view = session.current_clue().unwrap();
+    assert_eq!(view.clue.poem, "Fixed a typo");
+    assert_eq!(view.knowledge, KnowledgeKind::Unaided, "Status is kept");
+    assert_eq!(session.history()[0].clue_code, new_clues.0[0].code);
+    assert!(session.try_solve("0", &mode, None).is_none());
+    assert!(session.try_solve("new answer", &mode, None).unwrap() > 0);
+}
+
+#[test]
+fn test_current_location() {
+    let clues = Clues::mock();
+    let mut session = Session::new(SessionId::new("TEST").unwrap(), clues.clone());
+    assert_eq!(session.current_location(), None, "No clue opened yet");
+    session.current_clue();
+    assert_eq!(
+        session.current_location(),
+        Some(clues.0[0].location.as_str())
+    );
+    session.skip_current_clue(None);
+    assert_eq!(
+        session.current_location(),
+        None,
+        "The next clue is not opened yet"
+    );
+    session.current_clue();
+    assert_eq!(
+        session.current_location(),
+        Some(clues.0[1].location.as_str())
+    );
+}
+
+#[test]
+fn test_multiple_choice_attempts() {
+    let mut clues = Clues::mock();
+    clues.0[0].kind = ClueKind::MultipleChoice {
+        options: vec![String::from("0"), String::from("1"), String::from("2")],
+        max_attempts: 2,
+    };
+    let mut session = Session::new(SessionId::new("TEST").unwrap(), clues.clone());
+    let mode = AnswerMode::Shared;
+    assert_eq!(session.current_clue().unwrap().attempts_left, Some(2));
+
+    assert_eq!(
+        session.try_solve("1", &mode, None),
+        None,
+        "Picking another clue's answer is not penalised"
+    );
+    assert_eq!(session.negative_points, 0);
+    assert_eq!(session.current_clue().unwrap().attempts_left, Some(1));
+
+    assert_eq!(session.try_solve("2", &mode, None), None);
+    assert_eq!(session.clues[0].1, Status::Declined, "No attempts left");
+    let view = session.current_clue().unwrap();
+    assert_eq!(view.clue, clues.0[1]);
+    assert_eq!(view.attempts_left, None, "Typed answers are unlimited");
+}
+
+#[test]
+fn test_solve_leaves_next_clue_unread() {
+    let mut clues = Clues::mock();
+    clues.0[1].kind = ClueKind::MultipleChoice {
+        options: vec![String::from("1"), String::from("2")],
+        max_attempts: 1,
+    };
+    let mut session = Session::new(SessionId::new("TEST").unwrap(), clues);
+    let mode = AnswerMode::Shared;
+
+    session.current_clue();
+    assert!(session.try_solve("0", &mode, None).unwrap() > 0);
+    assert_eq!(
+        session.clues[1].1,
+        Status::Unread,
+        "The next clue's clock starts when the team opens it"
+    );
+
+    session.current_clue();
+    assert!(session.try_solve("1", &mode, None).unwrap() > 0);
+    assert!(matches!(session.clues[1].1, Status::Solved { .. }));
+    assert_eq!(session.clues[2].1, Status::Unread);
+}
+
+#[test]
+fn test_try_solve_scanned() {
+    let clues = Clues::mock();
+    let mut session = Session::new(SessionId::new("TEST").unwrap(), clues.clone());
+    let key = ScanKey::new("secret");
+    session.current_clue();
+
+    assert_eq!(session.try_solve_scanned("0123", &key, None), None);
+    let other = key.token(&clues.0[2]);
+    assert_eq!(
+        session.try_solve_scanned(&other, &key, None),
+        Some(-OTHER_CLUE_PENALTY)
+    );
+    let token = key.token(&clues.0[0]);
+    assert!(
+        session
+            .try_solve_scanned(&token, &key, Some("Alice"))
+            .unwrap()
+            > 0
+    );
+    assert_eq!(session.current_clue().unwrap().clue, clues.0[1]);
+    assert_eq!(
+        session.history().last().unwrap().event,
+        HistoryEvent::Guess {
+            answer: format!("QR code {token}"),
+            outcome: GuessOutcome::Correct
+        }
+    );
+
+    // Scanning the solved clue again, e.g. by reloading the page, costs nothing.
+    let score = session.total_score();
+    let n_events = session.history().len();
+    assert!(session.has_solved_scanned(&token, &key));
+    assert_eq!(session.try_solve_scanned(&token, &key, None), None);
+    assert_eq!(session.total_score(), score);
+    assert_eq!(session.history().len(), n_events);
 }
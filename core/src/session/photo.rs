@@ -0,0 +1,145 @@
+//! Clues answered by uploading a photo, which the game master approves or rejects.
+
+use {
+    super::{
+        Session,
+        history::{HistoryEntry, HistoryEvent},
+    },
+    crate::clues::{Clue, kind::ClueKind, status::Status},
+    std::time::SystemTime,
+};
+
+/// A photo waiting for the game master's approval.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingPhoto<'a> {
+    /// Position of the clue in the team's arrangement, starting from 1.
+    pub number: usize,
+    pub clue: &'a Clue,
+    /// The file name the photo was saved with.
+    pub file: &'a str,
+    pub member: Option<&'a str>,
+    pub time: SystemTime,
+}
+
+impl Session {
+    /// Submit a photo, saved as `file`, answering the current clue. The clue waits for
+    /// the game master's review and the team moves on to its next clue. Returns the
+    /// code of the clue, or `None` if the current clue is not answered with a photo.
+    pub fn submit_photo(&mut self, file: &str, member: Option<&str>) -> Option<[u8; 32]> {
+        let (clue, status) = self.inner_current_clue()?;
+        if clue.kind != ClueKind::Photo {
+            return None;
+        }
+        let clue_code = clue.code;
+        status.submitted();
+        let event = HistoryEvent::PhotoSubmitted { file: file.into() };
+        self.history
+            .push(HistoryEntry::now(clue_code, member, event));
+        Some(clue_code)
+    }
+
+    /// Approve or reject the photo submitted for the clue with the given code.
+    /// Returns false if the clue is not waiting for a review.
+    pub fn review_photo(&mut self, clue_code: &[u8; 32], approved: bool) -> bool {
+        let Some((_, status)) = self
+            .clues
+            .iter_mut()
+            .find(|(clue, _)| &clue.code == clue_code)
+        else {
+            return false;
+        };
+        let is_reviewed = if approved {
+            status.approve()
+        } else {
+            status.reject()
+        };
+        if is_reviewed {
+            let event = HistoryEvent::PhotoReviewed { approved };
+            self.history
+                .push(HistoryEntry::now(*clue_code, None, event));
+        }
+        is_reviewed
+    }
+
+    /// The photos waiting for the game master's review, in the order of the arrangement.
+    pub fn pending_photos(&self) -> Vec<PendingPhoto<'_>> {
+        self.clues
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, status))| matches!(status, Status::Pending { .. }))
+            .filter_map(|(i, (clue, _))| {
+                let entry = self.history.iter().rev().find(|entry| {
+                    entry.clue_code == clue.code
+                        && matches!(entry.event, HistoryEvent::PhotoSubmitted { .. })
+                })?;
+                let HistoryEvent::PhotoSubmitted { file } = &entry.event else {
+                    return None;
+                };
+                Some(PendingPhoto {
+                    number: i + 1,
+                    clue,
+                    file,
+                    member: entry.member.as_deref(),
+                    time: entry.time,
+                })
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_photo() {
+    use crate::{
+        clues::{Clues, team_code::AnswerMode},
+        session::SessionId,
+    };
+
+    let mut clues = Clues::mock();
+    clues.0[0].kind = ClueKind::Photo;
+    let photo_code = clues.0[0].code;
+    let mut session = Session::new(SessionId::new("TEST").unwrap(), clues.clone());
+
+    session.current_clue();
+    assert_eq!(
+        session.try_solve(&clues.0[0].id, &AnswerMode::Shared, None),
+        None,
+        "Photo clues cannot be solved by typing their answer"
+    );
+    assert_eq!(
+        session.submit_photo("TEST-1.jpg", Some("Alice")),
+        Some(photo_code)
+    );
+    assert_eq!(
+        session.current_clue().unwrap().clue,
+        clues.0[1],
+        "The team moves on while the photo is reviewed"
+    );
+    assert_eq!(
+        session.submit_photo("TEST-2.jpg", None),
+        None,
+        "The next clue is answered by typing"
+    );
+    let pending = session.pending_photos();
+    assert_eq!(pending.len(), 1);
+    assert_eq!((pending[0].number, pending[0].file), (1, "TEST-1.jpg"));
+    assert_eq!(pending[0].member, Some("Alice"));
+
+    // Rejecting the photo sends the team back to the clue once the others are done.
+    assert!(session.review_photo(&photo_code, false));
+    assert!(
+        !session.review_photo(&photo_code, false),
+        "Already reviewed"
+    );
+    assert!(matches!(session.clues[0].1, Status::Skipped { .. }));
+    assert!(session.pending_photos().is_empty());
+
+    session.clues[0].1 = Status::Seen {
+        kind: crate::clues::status::KnowledgeKind::Unaided,
+        time: std::time::Instant::now(),
+    };
+    session.clues[1].1 = Status::Declined;
+    assert!(session.submit_photo("TEST-1b.jpg", None).is_some());
+    assert!(session.review_photo(&photo_code, true));
+    assert!(session.clues[0].1.score() > 300);
+    assert_eq!(session.standing().clues_solved, 1);
+}
@@ -3,10 +3,11 @@
 use {
     crate::{
         clues::{
-            Clue,
+            self, Clue,
+            kind::ClueKind,
             status::{KnowledgeKind, Status},
         },
-        session::{Session, SessionId},
+        session::{Session, SessionId, history::HistoryEntry},
     },
     serde::{Deserialize, Serialize},
     std::{
@@ -20,15 +21,24 @@ pub struct SerializableSession<'a> {
     id: String,
     clues: Vec<(SerializableClue<'a>, SerializableStatus)>,
     negative_points: i32,
+    #[serde(default)]
+    credit_points: i32,
+    #[serde(default)]
+    history: Cow<'a, [HistoryEntry]>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SerializableClue<'a> {
+    /// Sessions saved before clues had IDs use the default ID.
+    #[serde(default)]
+    id: Option<Cow<'a, str>>,
     poem: Cow<'a, str>,
     hint: Cow<'a, str>,
     item: Cow<'a, str>,
     location: Cow<'a, str>,
     code: [u8; 32],
+    #[serde(default)]
+    kind: ClueKind,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -46,6 +56,10 @@ pub enum SerializableStatus {
         kind: SerializableKnowledgeKind,
         duration: Duration,
     },
+    Pending {
+        kind: SerializableKnowledgeKind,
+        duration: Duration,
+    },
     Declined,
 }
 
@@ -66,6 +80,8 @@ impl<'a> From<&'a Session> for SerializableSession<'a> {
                 .map(|(clue, status)| (clue.into(), status.into()))
                 .collect(),
             negative_points: value.negative_points,
+            credit_points: value.credit_points,
+            history: Cow::Borrowed(&value.history),
         }
     }
 }
@@ -80,6 +96,8 @@ impl<'a> From<SerializableSession<'a>> for Session {
                 .map(|(clue, status)| (clue.into(), status.into()))
                 .collect(),
             negative_points: value.negative_points,
+            credit_points: value.credit_points,
+            history: value.history.into_owned(),
         }
     }
 }
@@ -87,11 +105,13 @@ impl<'a> From<SerializableSession<'a>> for Session {
 impl<'a> From<&'a Clue> for SerializableClue<'a> {
     fn from(value: &'a Clue) -> Self {
         Self {
+            id: Some(Cow::Borrowed(&value.id)),
             poem: Cow::Borrowed(&value.poem),
             hint: Cow::Borrowed(&value.hint),
             item: Cow::Borrowed(&value.item),
             location: Cow::Borrowed(&value.location),
             code: value.code,
+            kind: value.kind.clone(),
         }
     }
 }
@@ -99,11 +119,15 @@ impl<'a> From<&'a Clue> for SerializableClue<'a> {
 impl<'a> From<SerializableClue<'a>> for Clue {
     fn from(value: SerializableClue<'a>) -> Self {
         Self {
+            id: value
+                .id
+                .map_or_else(|| clues::default_id(&value.code), Cow::into_owned),
             poem: value.poem.into_owned(),
             hint: value.hint.into_owned(),
             item: value.item.into_owned(),
             location: value.location.into_owned(),
             code: value.code,
+            kind: value.kind,
         }
     }
 }
@@ -130,6 +154,10 @@ impl<'a> From<&'a Status> for SerializableStatus {
                 kind: kind.into(),
                 duration: *duration,
             },
+            Status::Pending { kind, duration } => Self::Pending {
+                kind: kind.into(),
+                duration: *duration,
+            },
             Status::Declined => Self::Declined,
         }
     }
@@ -157,6 +185,10 @@ impl From<SerializableStatus> for Status {
                 kind: kind.into(),
                 duration,
             },
+            SerializableStatus::Pending { kind, duration } => Self::Pending {
+                kind: kind.into(),
+                duration,
+            },
             SerializableStatus::Declined => Self::Declined,
         }
     }
@@ -185,7 +217,15 @@ impl From<SerializableKnowledgeKind> for KnowledgeKind {
 #[test]
 fn test_serialization_round_trip() {
     let clues = crate::clues::Clues::mock();
-    let session = Session::new(clues);
+    let mut session = Session::new(SessionId::new("TEST").unwrap(), clues);
+    session.history.push(HistoryEntry::now(
+        session.clues[0].0.code,
+        Some("Alice"),
+        crate::session::history::HistoryEvent::Guess {
+            answer: "wrong answer".into(),
+            outcome: crate::session::history::GuessOutcome::Wrong,
+        },
+    ));
     let json = session.to_json().unwrap();
     std::thread::sleep(Duration::from_millis(50));
     let round_trip = Session::from_json(json).unwrap();
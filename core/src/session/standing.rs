@@ -0,0 +1,197 @@
+//! A team's position relative to other teams, for leaderboards.
+
+use {
+    super::{
+        Session,
+        history::{GuessOutcome, HistoryEvent},
+    },
+    crate::clues::status::Status,
+    serde::{Deserialize, Serialize},
+    std::{
+        cmp::Ordering,
+        collections::HashSet,
+        time::{Duration, SystemTime},
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Standing {
+    pub score: i32,
+    pub clues_solved: usize,
+    /// Clues which are neither solved nor declined.
+    pub clues_remaining: usize,
+    /// Time spent on the clues which were solved.
+    pub total_time: Duration,
+    pub last_solve: Option<SystemTime>,
+    /// Number of clues for which the team asked for a hint or to reveal the item, or
+    /// was helped by the game master.
+    pub hints_used: usize,
+    /// Number of clues solved since the team last skipped a clue.
+    pub streak: usize,
+}
+
+impl Standing {
+    /// Orders standings from best to worst. Ties on score are broken by more clues
+    /// solved, then less total time, then an earlier last solve.
+    pub fn ranking_cmp(&self, other: &Self) -> Ordering {
+        other
+            .score
+            .cmp(&self.score)
+            .then(other.clues_solved.cmp(&self.clues_solved))
+            .then(self.total_time.cmp(&other.total_time))
+            .then_with(|| match (self.last_solve, other.last_solve) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rank {
+    /// Position starting from 1. Tied teams share the position of the first of them.
+    pub position: usize,
+    pub is_tied: bool,
+}
+
+/// The rank of each of the `standings`, which must be sorted with `Standing::ranking_cmp`.
+pub fn ranks(standings: &[&Standing]) -> Vec<Rank> {
+    let ties_previous = |i: usize| {
+        i > 0
+            && i < standings.len()
+            && standings[i - 1].ranking_cmp(standings[i]) == Ordering::Equal
+    };
+    let mut ranks: Vec<Rank> = Vec::with_capacity(standings.len());
+    for i in 0..standings.len() {
+        let position = match ranks.last() {
+            Some(previous) if ties_previous(i) => previous.position,
+            _ => i + 1,
+        };
+        ranks.push(Rank {
+            position,
+            is_tied: ties_previous(i) || ties_previous(i + 1),
+        });
+    }
+    ranks
+}
+
+impl Session {
+    pub fn standing(&self) -> Standing {
+        let mut clues_solved = 0;
+        let mut clues_remaining = 0;
+        let mut total_time = Duration::ZERO;
+        for (_, status) in &self.clues {
+            match status {
+                Status::Solved { duration, .. } => {
+                    clues_solved += 1;
+                    total_time += *duration;
+                }
+                Status::Declined => (),
+                Status::Unread
+                | Status::Seen { .. }
+                | Status::Skipped { .. }
+                | Status::Pending { .. } => {
+                    clues_remaining += 1;
+                }
+            }
+        }
+
+        let mut last_solve = None;
+        let mut helped = HashSet::new();
+        let mut streak = 0;
+        for entry in &self.history {
+            match &entry.event {
+                HistoryEvent::Guess {
+                    outcome: GuessOutcome::Correct,
+                    ..
+                }
+                | HistoryEvent::PhotoReviewed { approved: true } => {
+                    last_solve = Some(entry.time);
+                    streak += 1;
+                }
+                HistoryEvent::Guess { .. }
+                | HistoryEvent::PhotoSubmitted { .. }
+                | HistoryEvent::PhotoReviewed { .. } => (),
+                HistoryEvent::Hint | HistoryEvent::Reveal | HistoryEvent::HelpReply { .. } => {
+                    helped.insert(entry.clue_code);
+                }
+                HistoryEvent::Skip => streak = 0,
+                HistoryEvent::HelpRequest { .. } => (),
+            }
+        }
+
+        Standing {
+            score: self.total_score(),
+            clues_solved,
+            clues_remaining,
+            total_time,
+            last_solve,
+            hints_used: helped.len(),
+            streak,
+        }
+    }
+}
+
+#[test]
+fn test_standing() {
+    use crate::{
+        clues::{Clues, team_code::AnswerMode},
+        session::SessionId,
+    };
+
+    let clues = Clues::mock();
+    let mut session = Session::new(SessionId::new("TEST").unwrap(), clues.clone());
+    let mode = AnswerMode::Shared;
+    session.current_clue();
+    session.hint_current_clue(None);
+    session.reveal_current_item(None);
+    session.try_solve(&clues.0[0].id, &mode, None);
+    session.current_clue();
+    session.skip_current_clue(None);
+    session.current_clue();
+    session.try_solve(&clues.0[2].id, &mode, None);
+
+    let standing = session.standing();
+    assert_eq!(standing.clues_solved, 2);
+    assert_eq!(standing.clues_remaining, clues.0.len() - 2);
+    assert_eq!(standing.hints_used, 1);
+    assert_eq!(standing.streak, 1, "Skipping resets the streak");
+    assert!(standing.last_solve.is_some());
+
+    let base = Standing {
+        score: 500,
+        clues_solved: 2,
+        clues_remaining: 3,
+        total_time: Duration::from_secs(600),
+        last_solve: Some(SystemTime::UNIX_EPOCH),
+        hints_used: 0,
+        streak: 2,
+    };
+    let more_solved = Standing {
+        clues_solved: 3,
+        ..base.clone()
+    };
+    let slower = Standing {
+        total_time: Duration::from_secs(700),
+        ..base.clone()
+    };
+    let higher = Standing {
+        score: 600,
+        ..slower.clone()
+    };
+    let mut standings = vec![&slower, &base, &more_solved, &higher, &base];
+    standings.sort_by(|a, b| a.ranking_cmp(b));
+    assert_eq!(
+        standings,
+        vec![&higher, &more_solved, &base, &base, &slower]
+    );
+    let positions: Vec<(usize, bool)> = ranks(&standings)
+        .into_iter()
+        .map(|rank| (rank.position, rank.is_tied))
+        .collect();
+    assert_eq!(
+        positions,
+        vec![(1, false), (2, false), (3, true), (3, true), (5, false)]
+    );
+}
@@ -0,0 +1,14 @@
+use treasure_hunt_server::{cli, config::Config};
+
+fn main() {
+    let result = Config::read()
+        .map_err(|e| anyhow::anyhow!("Failed to read the configuration: {e}"))
+        .and_then(|config| {
+            let args: Vec<String> = std::env::args().skip(1).collect();
+            cli::run(&config, &args)
+        });
+    if let Err(e) = result {
+        eprintln!("{e}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,118 @@
+//! Commands for checking the clue files while preparing a hunt.
+
+use {
+    crate::{config::Config, state::hunt::HuntId},
+    std::{
+        collections::{HashMap, HashSet},
+        path::Path,
+    },
+    treasure_hunt_core::clues::{Clue, Clues, arrangement::Arrangements},
+};
+
+/// Read the clue file of every hunt, printing what it holds and anything which looks
+/// wrong. Fails if the server would not start with these clue files.
+pub fn validate(config: &Config) -> anyhow::Result<()> {
+    let mut n_invalid = 0;
+    for hunt in config.hunts() {
+        println!("{} ({}): {}", hunt.name, hunt.id, hunt.clues_path);
+        match read_clues(&hunt.id, &hunt.clues_path) {
+            Ok((clues, _)) => {
+                let locations: HashSet<&str> = clues
+                    .iter()
+                    .map(|(clue, _)| clue.location.as_str())
+                    .collect();
+                println!("  {} clues at {} locations", clues.len(), locations.len());
+                for warning in warnings(&clues) {
+                    println!("  Warning: {warning}");
+                }
+            }
+            Err(e) => {
+                println!("  Error: {e}");
+                n_invalid += 1;
+            }
+        }
+    }
+    if n_invalid > 0 {
+        anyhow::bail!("{n_invalid} hunt(s) have invalid clues");
+    }
+    Ok(())
+}
+
+/// Print the order of the clues in some arrangements of a hunt, by default the first.
+pub fn arrangements(config: &Config, hunt_id: Option<&str>) -> anyhow::Result<()> {
+    let hunts = config.hunts();
+    let hunt = match hunt_id {
+        Some(id) => hunts.iter().find(|hunt| hunt.id == id),
+        None => hunts.first(),
+    }
+    .ok_or_else(|| anyhow::anyhow!("No hunt has ID {}", hunt_id.unwrap_or_default()))?;
+    let (_, arrangements) = read_clues(&hunt.id, &hunt.clues_path)?;
+    println!("The server arranges the clues again each time it starts, so these are examples.");
+    for (i, arrangement) in arrangements.precomputed().iter().enumerate() {
+        println!("\nArrangement {}:", i + 1);
+        for (number, clue) in arrangement.0.iter().enumerate() {
+            println!("  {}. {} ({})", number + 1, clue.location, clue.id);
+        }
+    }
+    Ok(())
+}
+
+/// The clues of a hunt with their answers, arranged as the server does when it starts.
+fn read_clues(
+    hunt_id: &str,
+    clues_path: &str,
+) -> anyhow::Result<(Vec<(Clue, String)>, Arrangements)> {
+    HuntId::new(hunt_id)?;
+    let clues = Clues::from_disk_with_answers(Path::new(clues_path))?;
+    let arrangements =
+        Arrangements::new(Clues(clues.iter().map(|(clue, _)| clue.clone()).collect()))
+            .map_err(anyhow::Error::msg)?;
+    Ok((clues, arrangements))
+}
+
+/// Problems which do not stop the hunt, but which teams would notice.
+fn warnings(clues: &[(Clue, String)]) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let mut ids_by_code: HashMap<&[u8; 32], &str> = HashMap::new();
+    for (clue, _) in clues {
+        if clue.poem.trim().is_empty() {
+            warnings.push(format!("Clue {} has no poem", clue.id));
+        }
+        if clue.hint.trim().is_empty() {
+            warnings.push(format!("Clue {} has no hint", clue.id));
+        }
+        if clue.item.trim().is_empty() {
+            warnings.push(format!("Clue {} has no item to reveal", clue.id));
+        }
+        if !clue.kind.accepts_typed_answer() {
+            continue;
+        }
+        if let Some(other) = ids_by_code.insert(&clue.code, &clue.id) {
+            warnings.push(format!(
+                "Clues {other} and {} have the same answer",
+                clue.id
+            ));
+        }
+    }
+    warnings
+}
+
+#[test]
+fn test_warnings() {
+    let mut clues: Vec<(Clue, String)> = ["A", "B", "C", "D"]
+        .into_iter()
+        .enumerate()
+        .map(|(seed, location)| (Clue::mock(seed as u64, location), seed.to_string()))
+        .collect();
+    assert!(warnings(&clues).is_empty());
+
+    clues[1].0.hint = String::new();
+    clues[3].0.code = clues[2].0.code;
+    assert_eq!(
+        warnings(&clues),
+        vec![
+            String::from("Clue 1 has no hint"),
+            String::from("Clues 2 and 3 have the same answer"),
+        ]
+    );
+}
@@ -0,0 +1,80 @@
+//! The `treasure-hunt` command-line tool, for preparing a hunt and for looking after its
+//! persisted state, e.g. `treasure-hunt export-results json`. Commands which change the
+//! state must be run while the server is stopped, or the server overwrites the change.
+
+use {
+    crate::{
+        config::Config,
+        export::results::{Format, Results},
+        printable::materials::{HuntMaterials, Material},
+        routes, state,
+    },
+    std::path::Path,
+};
+
+mod clues;
+mod teams;
+
+const USAGE: &str = "Usage: treasure-hunt <COMMAND>
+
+Commands:
+  validate                    Check the clue file of every hunt
+  arrangements [HUNT_ID]      Show example arrangements of a hunt's clues
+  teams                       List the teams in leaderboard order
+  team <SESSION_ID>           Show a team's members, score and clues
+  adjust-score <SESSION_ID> <POINTS>
+                              Give a team extra points, or negative points to take some away
+  export-results <json|teams-csv|clues-csv> [OUTPUT_PATH]
+                              Export the results of every team
+  print <labels|cheat-sheet> [OUTPUT_PATH]
+                              Make a page to print while preparing the hunt
+
+The configuration is read from config.json, or the path in TRH_CONFIG_PATH.";
+
+pub fn run(config: &Config, args: &[String]) -> anyhow::Result<()> {
+    match args {
+        [command] if command == "validate" => clues::validate(config),
+        [command, rest @ ..] if command == "arrangements" && rest.len() <= 1 => {
+            clues::arrangements(config, rest.first().map(String::as_str))
+        }
+        [command] if command == "teams" => teams::list(config),
+        [command, session_id] if command == "team" => teams::show(config, session_id),
+        [command, session_id, points] if command == "adjust-score" => {
+            teams::adjust_score(config, session_id, points)
+        }
+        [command, format, rest @ ..] if command == "export-results" && rest.len() <= 1 => {
+            let format = Format::parse(format).ok_or_else(|| anyhow::anyhow!(USAGE))?;
+            let sessions = state::read_persisted_sessions(Path::new(&config.state_persist_path))?;
+            let output = Results::new(sessions.values()).to_format(format)?;
+            match rest.first() {
+                Some(path) => std::fs::write(path, output)?,
+                None => print!("{output}"),
+            }
+            Ok(())
+        }
+        [command, material, rest @ ..] if command == "print" && rest.len() <= 1 => {
+            let material = Material::parse(material).ok_or_else(|| anyhow::anyhow!(USAGE))?;
+            if material == Material::Routes {
+                // Arrangements are chosen at random when the server starts.
+                anyhow::bail!("Print route sheets from the running server's admin pages");
+            }
+            let hunts = config
+                .hunts()
+                .iter()
+                .map(|hunt| HuntMaterials::new(hunt, &[]))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            let output =
+                routes::render_standalone(material.template(), minijinja::context! { hunts })?;
+            match rest.first() {
+                Some(path) => std::fs::write(path, output)?,
+                None => print!("{output}"),
+            }
+            Ok(())
+        }
+        [command] if command == "help" => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        _ => anyhow::bail!(USAGE),
+    }
+}
@@ -0,0 +1,109 @@
+//! Commands for looking at and correcting the teams in the persisted state.
+
+use {
+    crate::{config::Config, export::results::Results, state},
+    std::{path::Path, time::Duration},
+    treasure_hunt_core::session::SessionId,
+};
+
+/// List every team in leaderboard order.
+pub fn list(config: &Config) -> anyhow::Result<()> {
+    let sessions = state::read_persisted_sessions(Path::new(&config.state_persist_path))?;
+    let results = Results::new(sessions.values());
+    if results.teams.is_empty() {
+        println!("No teams have registered");
+        return Ok(());
+    }
+    println!(
+        "{:>4}  {:<12}  {:<12}  {:>6}  {:>6}  Team",
+        "Rank", "Hunt", "Session ID", "Score", "Solved"
+    );
+    for team in &results.teams {
+        println!(
+            "{:>4}  {:<12}  {:<12}  {:>6}  {:>6}  {}",
+            team.rank,
+            team.hunt,
+            team.session_id,
+            team.total_score,
+            team.clues_solved,
+            team.team_name
+        );
+    }
+    Ok(())
+}
+
+/// Show one team's members, score and progress through its clues.
+pub fn show(config: &Config, session_id: &str) -> anyhow::Result<()> {
+    let session_id = parse_session_id(session_id)?;
+    let sessions = state::read_persisted_sessions(Path::new(&config.state_persist_path))?;
+    let team_session = sessions
+        .get(&session_id)
+        .ok_or_else(|| anyhow::anyhow!("No team has session ID {session_id}"))?;
+    let results = Results::new(sessions.values());
+    let team = results
+        .teams
+        .iter()
+        .find(|team| team.session_id == session_id.as_str())
+        .expect("Every team has results");
+
+    println!(
+        "{} ({}), hunt {}",
+        team.team_name, team.session_id, team.hunt
+    );
+    let members: Vec<String> = team_session
+        .members
+        .iter()
+        .map(|member| member.to_string())
+        .collect();
+    if !members.is_empty() {
+        println!("Members: {}", members.join(", "));
+    }
+    println!(
+        "Rank {} with {} points: {} clues solved, {} penalty, {} credit, {} for help",
+        team.rank,
+        team.total_score,
+        team.clues_solved,
+        team.penalty_points,
+        team.credit_points,
+        team.help_points
+    );
+    for clue in &team.clues {
+        let mut line = format!(
+            "  {}. {} at {}: {}",
+            clue.number, clue.clue_id, clue.location, clue.status
+        );
+        if let Some(knowledge) = clue.knowledge {
+            line.push_str(&format!(" with {knowledge}"));
+        }
+        if let Some(seconds) = clue.duration_seconds {
+            let duration = humantime::format_duration(Duration::from_secs(seconds));
+            line.push_str(&format!(" in {duration}"));
+        }
+        println!("{line}, {} points", clue.points);
+    }
+    Ok(())
+}
+
+/// Give a team extra points, or take some away, e.g. for a clue which was hidden badly.
+pub fn adjust_score(config: &Config, session_id: &str, points: &str) -> anyhow::Result<()> {
+    let session_id = parse_session_id(session_id)?;
+    let points: i32 = points
+        .parse()
+        .map_err(|_| anyhow::anyhow!("{points:?} is not a whole number of points"))?;
+    let total_score = state::update_persisted_session(
+        Path::new(&config.state_persist_path),
+        session_id,
+        |session| {
+            session.grant_credit(points);
+            session.total_score()
+        },
+    )?;
+    println!("Team {session_id} now has {total_score} points");
+    Ok(())
+}
+
+/// Session IDs as they are shown to teams, ignoring dashes and case.
+fn parse_session_id(input: &str) -> anyhow::Result<SessionId> {
+    SessionId::new(&input.replace('-', ""))
+        .ok_or_else(|| anyhow::anyhow!("{input:?} is not a session ID"))
+}
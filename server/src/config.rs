@@ -1,18 +1,62 @@
 use {
+    crate::state::hunt::DEFAULT_HUNT_ID,
     serde::{Deserialize, Serialize},
-    std::{env, io, path::Path, str::FromStr},
+    std::{env, io, path::Path, str::FromStr, time::SystemTime},
+    treasure_hunt_core::session::SessionIdFormat,
 };
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
+    /// Clues for the single hunt which is run when `hunts` is empty.
+    #[serde(default)]
     pub clues_path: String,
+    /// The hunts run on this server. The first one is the default at registration.
+    #[serde(default)]
+    pub hunts: Vec<HuntConfig>,
+    /// If present, the clue files are checked for changes this often and reloaded.
+    /// Changes to answers are only applied from the admin reload page.
+    pub clue_reload_poll_seconds: Option<u64>,
     pub log_level: LogLevel,
     pub port: usize,
+    /// How long teams must spend on a clue before asking for a hint, revealing the item
+    /// or skipping it, unless their hunt sets its own.
     pub min_hint_seconds: u64,
     pub min_reveal_seconds: u64,
     pub min_skip_seconds: u64,
     pub state_channel_size: usize,
     pub state_persist_path: String,
+    /// If present, teams must submit team-specific answer codes derived from this secret.
+    /// Like the other secrets, it is never serialized, so it stays out of the logs.
+    #[serde(skip_serializing)]
+    pub team_codes_secret: Option<String>,
+    /// If present, each clue has a QR code which teams scan to answer it, made with this secret.
+    #[serde(skip_serializing)]
+    pub scan_secret: Option<String>,
+    /// The address teams use to reach the server, e.g. `"https://hunt.example.com"`, for
+    /// printed QR codes. If absent, the address the admin used to open the page is used.
+    pub public_url: Option<String>,
+    /// If present, the admin pages are served under `/admin/{admin_key}/`.
+    #[serde(skip_serializing)]
+    pub admin_key: Option<String>,
+    /// Secret used to sign session cookies. If absent, a random secret is generated at startup.
+    #[serde(skip_serializing)]
+    pub cookie_secret: Option<String>,
+    #[serde(default)]
+    pub guess_limits: GuessLimitConfig,
+    /// Limits on attempts to enter a team's PIN when logging in.
+    #[serde(default)]
+    pub login_limits: GuessLimitConfig,
+    #[serde(default)]
+    pub session_ids: SessionIdFormat,
+    /// If present, teams which register after others have started are given some points.
+    pub late_join_credit: Option<LateJoinCreditConfig>,
+    /// When the hunts end, in UTC, e.g. `"2026-10-19T18:00:00Z"`, unless they set their own.
+    pub end_time: Option<Timestamp>,
+    /// If present along with an end time, teams see the leaderboard of their hunt as it was
+    /// this many minutes before the hunt ends, until an admin unfreezes it.
+    pub leaderboard_freeze_minutes: Option<u64>,
+    #[serde(default)]
+    pub photos: PhotoConfig,
 }
 
 impl Config {
@@ -25,6 +69,159 @@ impl Config {
         let config: Self = serde_json::from_str(&string)?;
         Ok(config)
     }
+
+    /// The configured hunts, or a single default hunt using `clues_path`.
+    pub fn hunts(&self) -> Vec<HuntConfig> {
+        if !self.hunts.is_empty() {
+            return self.hunts.clone();
+        }
+        vec![HuntConfig {
+            id: DEFAULT_HUNT_ID.into(),
+            name: "Treasure Hunt".into(),
+            clues_path: self.clues_path.clone(),
+            ..HuntConfig::default()
+        }]
+    }
+
+    /// The settings of the hunt with ID `hunt_id`, using the server-wide ones where the
+    /// hunt does not set its own.
+    pub fn hunt_settings(&self, hunt_id: &str) -> HuntSettings {
+        let hunt = self.hunts.iter().find(|hunt| hunt.id == hunt_id);
+        HuntSettings {
+            min_hint_seconds: hunt
+                .and_then(|hunt| hunt.min_hint_seconds)
+                .unwrap_or(self.min_hint_seconds),
+            min_reveal_seconds: hunt
+                .and_then(|hunt| hunt.min_reveal_seconds)
+                .unwrap_or(self.min_reveal_seconds),
+            min_skip_seconds: hunt
+                .and_then(|hunt| hunt.min_skip_seconds)
+                .unwrap_or(self.min_skip_seconds),
+            end_time: hunt.and_then(|hunt| hunt.end_time).or(self.end_time),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HuntConfig {
+    /// Used in URLs, e.g. `/hunt/{id}/leaderboard`.
+    pub id: String,
+    pub name: String,
+    pub clues_path: String,
+    /// Overrides the server-wide setting of the same name for this hunt.
+    #[serde(default)]
+    pub min_hint_seconds: Option<u64>,
+    /// Overrides the server-wide setting of the same name for this hunt.
+    #[serde(default)]
+    pub min_reveal_seconds: Option<u64>,
+    /// Overrides the server-wide setting of the same name for this hunt.
+    #[serde(default)]
+    pub min_skip_seconds: Option<u64>,
+    /// Overrides the server-wide setting of the same name for this hunt.
+    #[serde(default)]
+    pub end_time: Option<Timestamp>,
+}
+
+/// The settings which each hunt can override; see `Config::hunt_settings`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HuntSettings {
+    pub min_hint_seconds: u64,
+    pub min_reveal_seconds: u64,
+    pub min_skip_seconds: u64,
+    pub end_time: Option<Timestamp>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GuessLimitConfig {
+    /// Maximum number of answers a team can submit in any one minute, or 0 for no limit.
+    pub max_guesses_per_minute: usize,
+    /// Number of wrong answers in a row which triggers a lockout. With 0, as with 1,
+    /// every wrong answer triggers one.
+    pub wrong_guesses_before_lockout: u32,
+    /// Length of the first lockout. Each further lockout is twice as long as the previous one.
+    pub lockout_seconds: u64,
+    pub max_lockout_seconds: u64,
+}
+
+impl Default for GuessLimitConfig {
+    fn default() -> Self {
+        Self {
+            max_guesses_per_minute: 10,
+            wrong_guesses_before_lockout: 5,
+            lockout_seconds: 30,
+            max_lockout_seconds: 600,
+        }
+    }
+}
+
+/// Where photos uploaded for photo clues are kept until the game master reviews them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PhotoConfig {
+    pub dir: String,
+    /// Largest photo a team can upload, in bytes.
+    pub max_bytes: usize,
+}
+
+impl Default for PhotoConfig {
+    fn default() -> Self {
+        Self {
+            dir: String::from("photos"),
+            max_bytes: 10 * 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LateJoinCreditConfig {
+    /// Fraction of the median score of the teams already in the hunt given to a new team.
+    pub fraction_of_median_score: f64,
+    /// The most points a new team can be given.
+    pub max_points: i32,
+}
+
+impl Default for LateJoinCreditConfig {
+    fn default() -> Self {
+        Self {
+            fraction_of_median_score: 0.5,
+            max_points: 500,
+        }
+    }
+}
+
+impl LateJoinCreditConfig {
+    /// Points given to a team joining a hunt in which the other teams have `scores`.
+    pub fn credit(&self, scores: &mut [i32]) -> i32 {
+        if scores.is_empty() {
+            return 0;
+        }
+        scores.sort_unstable();
+        let median = scores[scores.len() / 2];
+        let credit = (f64::from(median) * self.fraction_of_median_score) as i32;
+        credit.clamp(0, self.max_points.max(0))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Timestamp {
+    pub inner: SystemTime,
+}
+
+impl TryFrom<String> for Timestamp {
+    type Error = humantime::TimestampError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        humantime::parse_rfc3339(&value).map(|inner| Self { inner })
+    }
+}
+
+impl From<Timestamp> for String {
+    fn from(value: Timestamp) -> Self {
+        humantime::format_rfc3339_seconds(value.inner).to_string()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,3 +251,97 @@ impl FromStr for LogLevel {
         tracing::Level::from_str(s).map(|inner| Self { inner })
     }
 }
+
+#[test]
+fn test_late_join_credit() {
+    let config = LateJoinCreditConfig::default();
+    assert_eq!(config.credit(&mut []), 0, "The first team gets no credit");
+    assert_eq!(config.credit(&mut [300, 100, 200]), 100);
+    assert_eq!(
+        config.credit(&mut [-50, -20, 0]),
+        0,
+        "Credit is never negative"
+    );
+    assert_eq!(config.credit(&mut [5_000, 4_000]), 500, "Credit is capped");
+}
+
+#[test]
+fn test_timestamp() {
+    let timestamp = Timestamp::try_from(String::from("2026-10-19T18:00:00Z")).unwrap();
+    assert_eq!(
+        timestamp
+            .inner
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs(),
+        1_792_432_800
+    );
+    assert_eq!(String::from(timestamp), "2026-10-19T18:00:00Z");
+    assert!(Timestamp::try_from(String::from("six o'clock")).is_err());
+}
+
+#[test]
+fn test_hunt_settings() {
+    let config: Config = serde_json::from_value(serde_json::json!({
+        "hunts": [
+            {"id": "kids", "name": "Kids", "clues_path": "kids.json", "min_skip_seconds": 60,
+             "end_time": "2026-10-19T17:00:00Z"},
+            {"id": "adults", "name": "Adults", "clues_path": "adults.json"},
+        ],
+        "log_level": "info",
+        "port": 8080,
+        "min_hint_seconds": 300,
+        "min_reveal_seconds": 600,
+        "min_skip_seconds": 900,
+        "state_channel_size": 16,
+        "state_persist_path": "state.json",
+        "end_time": "2026-10-19T18:00:00Z",
+    }))
+    .unwrap();
+    let end_time = |s: &str| Some(Timestamp::try_from(String::from(s)).unwrap());
+    assert_eq!(
+        config.hunt_settings("kids"),
+        HuntSettings {
+            min_hint_seconds: 300,
+            min_reveal_seconds: 600,
+            min_skip_seconds: 60,
+            end_time: end_time("2026-10-19T17:00:00Z"),
+        }
+    );
+    assert_eq!(
+        config.hunt_settings("adults"),
+        HuntSettings {
+            min_hint_seconds: 300,
+            min_reveal_seconds: 600,
+            min_skip_seconds: 900,
+            end_time: end_time("2026-10-19T18:00:00Z"),
+        }
+    );
+}
+
+#[test]
+fn test_secrets_not_serialized() {
+    let config: Config = serde_json::from_value(serde_json::json!({
+        "log_level": "info",
+        "port": 8080,
+        "min_hint_seconds": 0,
+        "min_reveal_seconds": 0,
+        "min_skip_seconds": 0,
+        "state_channel_size": 16,
+        "state_persist_path": "state.json",
+        "team_codes_secret": "team-codes-secret",
+        "scan_secret": "scan-secret",
+        "cookie_secret": "cookie-secret",
+        "admin_key": "admin-key",
+    }))
+    .unwrap();
+    let serialized = serde_json::to_string(&config).unwrap();
+    for secret in [
+        "team-codes-secret",
+        "scan-secret",
+        "cookie-secret",
+        "admin-key",
+    ] {
+        assert!(!serialized.contains(secret), "{secret} was serialized");
+    }
+}
@@ -0,0 +1,14 @@
+//! Data exported from the server for use after the hunt.
+
+use serde::Serialize;
+
+pub mod results;
+
+/// Write `rows` as CSV with a header line taken from the field names.
+pub fn to_csv<T: Serialize>(rows: &[T]) -> anyhow::Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for row in rows {
+        writer.serialize(row)?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
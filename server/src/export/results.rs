@@ -0,0 +1,301 @@
+//! Final results of every team, for publishing after the hunt. They can be exported
+//! from the admin pages while the server runs, or from the persisted state with
+//! `treasure-hunt export-results` once it has stopped.
+
+use {
+    crate::state::TeamSession,
+    serde::Serialize,
+    treasure_hunt_core::{
+        clues::status::{KnowledgeKind, Status},
+        session::standing::{self, Standing},
+    },
+};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TeamResult {
+    /// Position in the hunt's leaderboard. Tied teams share a rank.
+    pub rank: usize,
+    pub hunt: String,
+    pub team_name: String,
+    pub session_id: String,
+    pub total_score: i32,
+    pub clues_solved: usize,
+    /// Points lost for submitting answers to other clues (zero or negative).
+    pub penalty_points: i32,
+    /// Points granted regardless of clues, e.g. for registering late.
+    pub credit_points: i32,
+    /// Points charged for the game master's help (zero or negative).
+    pub help_points: i32,
+    pub clues: Vec<ClueResult>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ClueResult {
+    /// Position of the clue in the team's arrangement, starting from 1.
+    pub number: usize,
+    pub clue_id: String,
+    pub location: String,
+    pub status: &'static str,
+    /// How much help the team had, if the clue was reached.
+    pub knowledge: Option<&'static str>,
+    pub duration_seconds: Option<u64>,
+    pub points: i32,
+}
+
+/// A row of the per-clue CSV export, which repeats the team's details on every row.
+#[derive(Debug, Serialize)]
+struct ClueRow<'a> {
+    hunt: &'a str,
+    team_name: &'a str,
+    session_id: &'a str,
+    number: usize,
+    clue_id: &'a str,
+    location: &'a str,
+    status: &'static str,
+    knowledge: Option<&'static str>,
+    duration_seconds: Option<u64>,
+    points: i32,
+}
+
+/// A row of the per-team CSV export.
+#[derive(Debug, Serialize)]
+struct TeamRow<'a> {
+    rank: usize,
+    hunt: &'a str,
+    team_name: &'a str,
+    session_id: &'a str,
+    total_score: i32,
+    clues_solved: usize,
+    penalty_points: i32,
+    credit_points: i32,
+    help_points: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    TeamsCsv,
+    CluesCsv,
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "json" => Some(Self::Json),
+            "teams-csv" => Some(Self::TeamsCsv),
+            "clues-csv" => Some(Self::CluesCsv),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::TeamsCsv | Self::CluesCsv => "text/csv",
+        }
+    }
+
+    pub fn filename(&self) -> &'static str {
+        match self {
+            Self::Json => "results.json",
+            Self::TeamsCsv => "results-teams.csv",
+            Self::CluesCsv => "results-clues.csv",
+        }
+    }
+}
+
+/// Results of all teams, sorted by hunt and then in leaderboard order.
+#[derive(Debug, Clone, Serialize)]
+pub struct Results {
+    pub teams: Vec<TeamResult>,
+}
+
+impl Results {
+    pub fn new<'a, I>(team_sessions: I) -> Self
+    where
+        I: IntoIterator<Item = &'a TeamSession>,
+    {
+        let mut teams: Vec<(TeamResult, Standing)> = team_sessions
+            .into_iter()
+            .map(|team_session| {
+                (
+                    TeamResult::new(team_session),
+                    team_session.session.standing(),
+                )
+            })
+            .collect();
+        teams.sort_by(|(a, a_standing), (b, b_standing)| {
+            a.hunt
+                .cmp(&b.hunt)
+                .then(a_standing.ranking_cmp(b_standing))
+                .then_with(|| a.team_name.cmp(&b.team_name))
+        });
+        for hunt_teams in teams.chunk_by_mut(|(a, _), (b, _)| a.hunt == b.hunt) {
+            let standings: Vec<&Standing> = hunt_teams.iter().map(|(_, s)| s).collect();
+            let ranks = standing::ranks(&standings);
+            for ((team, _), rank) in hunt_teams.iter_mut().zip(ranks) {
+                team.rank = rank.position;
+            }
+        }
+        Self {
+            teams: teams.into_iter().map(|(team, _)| team).collect(),
+        }
+    }
+
+    pub fn to_format(&self, format: Format) -> anyhow::Result<String> {
+        match format {
+            Format::Json => Ok(serde_json::to_string_pretty(self)?),
+            Format::TeamsCsv => super::to_csv(&self.team_rows()),
+            Format::CluesCsv => super::to_csv(&self.clue_rows()),
+        }
+    }
+
+    fn team_rows(&self) -> Vec<TeamRow<'_>> {
+        self.teams
+            .iter()
+            .map(|team| TeamRow {
+                rank: team.rank,
+                hunt: &team.hunt,
+                team_name: &team.team_name,
+                session_id: &team.session_id,
+                total_score: team.total_score,
+                clues_solved: team.clues_solved,
+                penalty_points: team.penalty_points,
+                credit_points: team.credit_points,
+                help_points: team.help_points,
+            })
+            .collect()
+    }
+
+    fn clue_rows(&self) -> Vec<ClueRow<'_>> {
+        self.teams
+            .iter()
+            .flat_map(|team| {
+                team.clues.iter().map(|clue| ClueRow {
+                    hunt: &team.hunt,
+                    team_name: &team.team_name,
+                    session_id: &team.session_id,
+                    number: clue.number,
+                    clue_id: &clue.clue_id,
+                    location: &clue.location,
+                    status: clue.status,
+                    knowledge: clue.knowledge,
+                    duration_seconds: clue.duration_seconds,
+                    points: clue.points,
+                })
+            })
+            .collect()
+    }
+}
+
+impl TeamResult {
+    fn new(team_session: &TeamSession) -> Self {
+        let session = &team_session.session;
+        let clues: Vec<ClueResult> = session
+            .clues()
+            .enumerate()
+            .map(|(i, (clue, status))| {
+                let (status_name, kind, duration) = match status {
+                    Status::Unread => ("Unread", None, None),
+                    Status::Seen { kind, .. } => ("Seen", Some(kind), None),
+                    Status::Skipped { kind, .. } => ("Skipped", Some(kind), None),
+                    Status::Solved { kind, duration } => {
+                        ("Solved", Some(kind), Some(duration.as_secs()))
+                    }
+                    Status::Pending { kind, duration } => {
+                        ("Pending", Some(kind), Some(duration.as_secs()))
+                    }
+                    Status::Declined => ("Declined", None, None),
+                };
+                ClueResult {
+                    number: i + 1,
+                    clue_id: clue.id.clone(),
+                    location: clue.location.clone(),
+                    status: status_name,
+                    knowledge: kind.map(knowledge_name),
+                    duration_seconds: duration,
+                    points: status.score(),
+                }
+            })
+            .collect();
+        Self {
+            // Set once all teams are sorted.
+            rank: 0,
+            hunt: team_session.hunt.to_string(),
+            team_name: team_session.name.to_string(),
+            session_id: session.id.to_string(),
+            total_score: session.total_score(),
+            clues_solved: clues.iter().filter(|c| c.status == "Solved").count(),
+            penalty_points: session.penalty_points(),
+            credit_points: session.credit_points(),
+            help_points: session.help_points(),
+            clues,
+        }
+    }
+}
+
+fn knowledge_name(kind: &KnowledgeKind) -> &'static str {
+    match kind {
+        KnowledgeKind::Unaided => "Unaided",
+        KnowledgeKind::WithHint => "WithHint",
+        KnowledgeKind::KnowingItem => "KnowingItem",
+    }
+}
+
+#[test]
+fn test_results() {
+    use {
+        crate::state::{TeamName, hunt::HuntId},
+        treasure_hunt_core::{
+            clues::{Clues, team_code::AnswerMode},
+            session::{Session, SessionId},
+        },
+    };
+
+    let clues = Clues::mock();
+    let hunt = HuntId::new("main").unwrap();
+    let mut team_sessions: Vec<TeamSession> = ["AAAA", "BBBB", "CCCC"]
+        .iter()
+        .map(|id| {
+            let session = Session::new(SessionId::new(id).unwrap(), clues.clone());
+            let name = TeamName::new(&format!("Team {id}")).unwrap();
+            TeamSession::new(name, hunt.clone(), session, None, String::from("CODE"))
+        })
+        .collect();
+    team_sessions[1].session.current_clue();
+    team_sessions[1]
+        .session
+        .try_solve(&clues.0[0].id, &AnswerMode::Shared, None);
+    team_sessions[1]
+        .session
+        .try_solve(&clues.0[5].id, &AnswerMode::Shared, None);
+
+    let results = Results::new(&team_sessions);
+    let winner = &results.teams[0];
+    assert_eq!(winner.team_name, "Team BBBB");
+    assert_eq!(winner.clues_solved, 1);
+    assert_eq!(winner.penalty_points, -100);
+    assert_eq!(winner.clues[0].status, "Solved");
+    assert_eq!(winner.clues[0].knowledge, Some("Unaided"));
+    assert!(winner.clues[0].points > 300);
+    assert_eq!(winner.clues[1].status, "Seen");
+    assert_eq!(winner.clues[2].knowledge, None);
+
+    let teams_csv = results.to_format(Format::TeamsCsv).unwrap();
+    let lines: Vec<&str> = teams_csv.lines().collect();
+    assert_eq!(
+        lines[0],
+        "rank,hunt,team_name,session_id,total_score,clues_solved,penalty_points,credit_points,help_points"
+    );
+    assert!(lines[1].starts_with("1,main,Team BBBB,BBBB,"));
+    assert!(
+        lines[2].starts_with("2,main,Team AAAA,") && lines[3].starts_with("2,main,Team CCCC,"),
+        "Tied teams share a rank: {teams_csv}"
+    );
+
+    let clues_csv = results.to_format(Format::CluesCsv).unwrap();
+    assert_eq!(clues_csv.lines().count(), 1 + 3 * clues.0.len());
+    assert!(clues_csv.starts_with(
+        "hunt,team_name,session_id,number,clue_id,location,status,knowledge,duration_seconds,points"
+    ));
+}
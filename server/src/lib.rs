@@ -0,0 +1,197 @@
+//! The treasure hunt web server, and the command-line tool for preparing and running hunts.
+
+use {
+    self::{
+        config::Config,
+        routes::auth::CookieKey,
+        state::{
+            command::Command, hunt::HuntId, member::TeamEvents, pin::PinGenerations,
+            spectator::SpectatorEvents,
+        },
+    },
+    axum::{
+        Router,
+        extract::DefaultBodyLimit,
+        routing::{get, post},
+    },
+    std::sync::Arc,
+    tokio::sync::mpsc,
+    tracing_subscriber::fmt::format::FmtSpan,
+};
+
+pub mod cli;
+pub mod config;
+mod export;
+mod printable;
+mod routes;
+mod state;
+
+fn set_global_tracing_subscriber(config: &Config) {
+    tracing_subscriber::fmt()
+        .with_max_level(config.log_level.inner)
+        .with_span_events(FmtSpan::CLOSE)
+        .init();
+}
+
+/// Run the web server until it is stopped.
+pub async fn serve(config: Config) {
+    set_global_tracing_subscriber(&config);
+
+    tracing::info!(
+        "Starting app with config: {}",
+        serde_json::to_string(&config).unwrap()
+    );
+
+    let (state, sender, state_writer) = state::State::new(&config).unwrap();
+
+    let events = state.events();
+    let spectator_events = state.spectator_events();
+    let pin_generations = state.pin_generations();
+    routes::show_announcements(state.announcements());
+    let watcher_task = state::watcher::ClueWatcher::new(&config, sender.clone())
+        .map(state::watcher::ClueWatcher::spawn);
+    // The state has checked the hunt IDs.
+    let freeze_tasks: Vec<_> = config
+        .hunts()
+        .iter()
+        .filter_map(|hunt| HuntId::new(&hunt.id).ok())
+        .filter_map(|hunt| state::freeze::FreezeTimer::new(&config, hunt, sender.clone()))
+        .map(state::freeze::FreezeTimer::spawn)
+        .collect();
+    let state_task = state.spawn();
+    let writer_task = state_writer.spawn();
+    let bind_url = format!("0.0.0.0:{}", config.port);
+    let route_state = RouteState {
+        sender,
+        events,
+        spectator_events,
+        pin_generations,
+        cookie_key: CookieKey::new(&config),
+        config: Arc::new(config),
+    };
+
+    let app = Router::new()
+        .route(
+            "/",
+            get(routes::register::form).post(routes::register::action),
+        )
+        .route("/login", post(routes::login::action))
+        .route("/join", post(routes::login::join))
+        .route("/join/{invite_code}", get(routes::login::join_form))
+        .route("/card", get(routes::card::action))
+        .route("/logout", get(routes::login::logout))
+        .route("/leaderboard", get(routes::leaderboard::action))
+        .route("/hunt/{hunt_id}", get(routes::register::hunt_form))
+        .route(
+            "/hunt/{hunt_id}/leaderboard",
+            get(routes::leaderboard::hunt_action),
+        )
+        .route("/spectator", get(routes::spectator::action))
+        .route(
+            "/hunt/{hunt_id}/spectator",
+            get(routes::spectator::hunt_action),
+        )
+        .route(
+            "/hunt/{hunt_id}/spectator/events",
+            get(routes::spectator::events),
+        )
+        .route("/clue", get(routes::clues::form))
+        .route("/progress", get(routes::progress::action))
+        .route("/about", get(routes::about::action))
+        .route("/codes", get(routes::team_codes::action))
+        .route("/events", get(routes::events::action))
+        .route("/hint/{clue_id}", post(routes::hint::hint_action))
+        .route("/help/{clue_id}", post(routes::help::action))
+        .route("/reveal/{clue_id}", post(routes::hint::reveal_action))
+        .route("/answer/{clue_id}", post(routes::answer::action))
+        .route("/skip/{clue_id}", post(routes::skip::action))
+        .route("/scan/{token}", get(routes::scan::action))
+        .route(
+            "/photo/{clue_id}",
+            // Leave room for the other form fields.
+            post(routes::photo::action).layer(DefaultBodyLimit::max(
+                route_state.config.photos.max_bytes + 64 * 1024,
+            )),
+        )
+        .route(
+            "/admin/{admin_key}/anomalies",
+            get(routes::admin::anomalies::action),
+        )
+        .route(
+            "/admin/{admin_key}/reload",
+            get(routes::admin::reload::form).post(routes::admin::reload::action),
+        )
+        .route(
+            "/admin/{admin_key}/statistics",
+            get(routes::admin::statistics::page),
+        )
+        .route(
+            "/admin/{admin_key}/statistics.json",
+            get(routes::admin::statistics::json),
+        )
+        .route(
+            "/admin/{admin_key}/statistics/clues.csv",
+            get(routes::admin::statistics::clues_csv),
+        )
+        .route(
+            "/admin/{admin_key}/statistics/timelines.csv",
+            get(routes::admin::statistics::timelines_csv),
+        )
+        .route(
+            "/admin/{admin_key}/results/{format}",
+            get(routes::admin::results::action),
+        )
+        .route(
+            "/admin/{admin_key}/announcements",
+            get(routes::admin::announcements::page).post(routes::admin::announcements::action),
+        )
+        .route(
+            "/admin/{admin_key}/help",
+            get(routes::admin::help::page).post(routes::admin::help::reply),
+        )
+        .route(
+            "/admin/{admin_key}/photos",
+            get(routes::admin::photos::page).post(routes::admin::photos::review),
+        )
+        .route(
+            "/admin/{admin_key}/photos/{file}",
+            get(routes::admin::photos::file),
+        )
+        .route(
+            "/admin/{admin_key}/leaderboard",
+            get(routes::admin::leaderboard::page).post(routes::admin::leaderboard::action),
+        )
+        .route(
+            "/admin/{admin_key}/print/{material}",
+            get(routes::admin::print::action),
+        )
+        .route(
+            "/admin/{admin_key}/qr",
+            get(routes::admin::scan_sheet::page),
+        )
+        .route(
+            "/admin/{admin_key}/pin",
+            get(routes::admin::pin::form).post(routes::admin::pin::action),
+        )
+        .with_state(route_state);
+    let listener = tokio::net::TcpListener::bind(bind_url).await.unwrap();
+    axum::serve(listener, app).await.unwrap();
+    state_task.await.unwrap();
+    writer_task.await.unwrap();
+    if let Some(watcher_task) = watcher_task {
+        watcher_task.await.unwrap();
+    }
+    for freeze_task in freeze_tasks {
+        freeze_task.await.unwrap();
+    }
+}
+
+#[derive(Debug, Clone)]
+struct RouteState {
+    sender: mpsc::Sender<Command>,
+    events: TeamEvents,
+    spectator_events: SpectatorEvents,
+    pin_generations: PinGenerations,
+    config: Arc<Config>,
+    cookie_key: CookieKey,
+}
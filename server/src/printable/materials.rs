@@ -0,0 +1,219 @@
+//! Pages for preparing the hunt: labels with the answers to stick on the items, the
+//! game master's cheat sheet, and the route each arrangement of clues takes.
+
+use {
+    crate::config::HuntConfig,
+    serde::Serialize,
+    std::{collections::BTreeMap, path::Path},
+    treasure_hunt_core::clues::{Clue, Clues, kind::ClueKind},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Material {
+    /// The answer to write on each item, one label per clue.
+    Labels,
+    /// Every clue with its poem, hint, item and answer, grouped by location.
+    CheatSheet,
+    /// The clues in the order of each arrangement, one sheet per arrangement.
+    Routes,
+}
+
+impl Material {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "labels" => Some(Self::Labels),
+            "cheat-sheet" => Some(Self::CheatSheet),
+            "routes" => Some(Self::Routes),
+            _ => None,
+        }
+    }
+
+    pub fn template(&self) -> &'static str {
+        match self {
+            Self::Labels => "print_labels.html",
+            Self::CheatSheet => "print_cheat_sheet.html",
+            Self::Routes => "print_routes.html",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct HuntMaterials {
+    pub name: String,
+    pub labels: Vec<Label>,
+    pub locations: Vec<LocationClues>,
+    pub routes: Vec<Route>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Label {
+    pub clue_id: String,
+    pub location: String,
+    pub answer: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LocationClues {
+    pub location: String,
+    pub clues: Vec<CheatSheetClue>,
+}
+
+/// A clue on the cheat sheet. The poem, hint and item come from the clue file, so they
+/// may contain HTML.
+#[derive(Debug, Serialize)]
+pub struct CheatSheetClue {
+    pub clue_id: String,
+    pub kind: &'static str,
+    pub poem: String,
+    pub hint: String,
+    pub item: String,
+    pub answer: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Route {
+    pub number: usize,
+    pub stops: Vec<Stop>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Stop {
+    pub number: usize,
+    pub clue_id: String,
+    pub location: String,
+    pub item: String,
+    pub answer: String,
+}
+
+impl HuntMaterials {
+    /// Read the hunt's clue file. The `arrangements` list the clue IDs in the order
+    /// teams are given them; any IDs no longer in the clue file are left out.
+    pub fn new(hunt: &HuntConfig, arrangements: &[Vec<String>]) -> anyhow::Result<Self> {
+        let clues = Clues::from_disk_with_answers(Path::new(&hunt.clues_path))?;
+
+        let labels = clues
+            .iter()
+            // Photo clues have no answer to find on the item.
+            .filter(|(clue, _)| clue.kind.accepts_typed_answer())
+            .map(|(clue, answer)| Label {
+                clue_id: clue.id.clone(),
+                location: clue.location.clone(),
+                answer: answer.clone(),
+            })
+            .collect();
+
+        let mut by_location: BTreeMap<&str, Vec<CheatSheetClue>> = BTreeMap::new();
+        for (clue, answer) in &clues {
+            by_location
+                .entry(&clue.location)
+                .or_default()
+                .push(CheatSheetClue {
+                    clue_id: clue.id.clone(),
+                    kind: kind_name(&clue.kind),
+                    poem: clue.poem.clone(),
+                    hint: clue.hint.clone(),
+                    item: clue.item.clone(),
+                    answer: describe_answer(clue, answer),
+                });
+        }
+        let locations = by_location
+            .into_iter()
+            .map(|(location, clues)| LocationClues {
+                location: location.into(),
+                clues,
+            })
+            .collect();
+
+        let routes = arrangements
+            .iter()
+            .enumerate()
+            .map(|(i, clue_ids)| Route {
+                number: i + 1,
+                stops: clue_ids
+                    .iter()
+                    .filter_map(|id| clues.iter().find(|(clue, _)| &clue.id == id))
+                    .enumerate()
+                    .map(|(j, (clue, answer))| Stop {
+                        number: j + 1,
+                        clue_id: clue.id.clone(),
+                        location: clue.location.clone(),
+                        item: clue.item.clone(),
+                        answer: describe_answer(clue, answer),
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Ok(Self {
+            name: hunt.name.clone(),
+            labels,
+            locations,
+            routes,
+        })
+    }
+}
+
+fn kind_name(kind: &ClueKind) -> &'static str {
+    match kind {
+        ClueKind::Text => "Typed answer",
+        ClueKind::Photo => "Photo",
+        ClueKind::MultipleChoice { .. } => "Multiple choice",
+        ClueKind::Numeric { .. } => "Number",
+        ClueKind::Sequence { .. } => "Sequence",
+    }
+}
+
+/// The answer as the game master needs to know it, e.g. with the tolerance of a number.
+fn describe_answer(clue: &Clue, answer: &str) -> String {
+    match &clue.kind {
+        ClueKind::Numeric { tolerance, .. } if *tolerance > 0.0 => {
+            format!("{answer} ± {tolerance}")
+        }
+        ClueKind::Photo => String::from("(photo)"),
+        _ => answer.into(),
+    }
+}
+
+#[test]
+fn test_materials() {
+    let path = std::env::temp_dir().join("test_materials_clues.json");
+    let clues = r#"[
+        {"id": "a", "poem": "P1", "hint": "H1", "item": "I1", "location": "Kitchen", "answer": "1"},
+        {"id": "b", "poem": "P2", "hint": "H2", "item": "I2", "location": "Attic", "answer": "x",
+         "kind": {"type": "photo"}},
+        {"id": "c", "poem": "P3", "hint": "H3", "item": "I3", "location": "Kitchen", "answer": "7",
+         "kind": {"type": "numeric", "tolerance": 0.5}}
+    ]"#;
+    std::fs::write(&path, clues).unwrap();
+    let hunt = HuntConfig {
+        id: String::from("main"),
+        name: String::from("Hunt"),
+        clues_path: path.to_string_lossy().into(),
+        ..HuntConfig::default()
+    };
+    let arrangements = vec![vec![
+        String::from("c"),
+        String::from("gone"),
+        String::from("a"),
+    ]];
+    let materials = HuntMaterials::new(&hunt, &arrangements).unwrap();
+    std::fs::remove_file(&path).ok();
+
+    let labels: Vec<_> = materials.labels.iter().map(|l| l.answer.as_str()).collect();
+    assert_eq!(labels, ["1", "7"], "Photo clues have no label");
+
+    let locations: Vec<_> = materials
+        .locations
+        .iter()
+        .map(|l| (l.location.as_str(), l.clues.len()))
+        .collect();
+    assert_eq!(locations, [("Attic", 1), ("Kitchen", 2)]);
+    assert_eq!(materials.locations[1].clues[1].answer, "7 ± 0.5");
+
+    let stops: Vec<_> = materials.routes[0]
+        .stops
+        .iter()
+        .map(|stop| (stop.number, stop.clue_id.as_str()))
+        .collect();
+    assert_eq!(stops, [(1, "c"), (2, "a")], "Unknown clues are left out");
+}
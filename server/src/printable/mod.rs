@@ -0,0 +1,41 @@
+//! Pages made from the clue files for printing before the hunt.
+
+use qrcode::{QrCode, render::svg};
+
+pub mod materials;
+pub mod scan_sheet;
+
+/// A QR code holding `data`, as an SVG element to put in an HTML page.
+pub fn qr_svg(data: &str) -> anyhow::Result<String> {
+    let svg = QrCode::new(data)?
+        .render::<svg::Color>()
+        .min_dimensions(160, 160)
+        .build();
+    // Drop the XML declaration, which only belongs at the start of an SVG file.
+    let start = svg.find("<svg").unwrap_or_default();
+    Ok(svg[start..].into())
+}
+
+/// The address in printed QR codes: the configured public URL, or else the host the
+/// admin used to reach the server.
+pub fn base_url(public_url: Option<&str>, host: Option<&str>) -> String {
+    match (public_url, host) {
+        (Some(url), _) => url.trim_end_matches('/').into(),
+        (None, Some(host)) => format!("http://{host}"),
+        (None, None) => String::from("http://localhost"),
+    }
+}
+
+#[test]
+fn test_qr_svg() {
+    let svg = qr_svg("https://hunt.example.com/scan/0123456789abcdef").unwrap();
+    assert!(svg.starts_with("<svg") && svg.ends_with("</svg>"));
+    assert_eq!(
+        base_url(Some("https://hunt.example.com/"), Some("10.0.0.1:3000")),
+        "https://hunt.example.com"
+    );
+    assert_eq!(
+        base_url(None, Some("10.0.0.1:3000")),
+        "http://10.0.0.1:3000"
+    );
+}
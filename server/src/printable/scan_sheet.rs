@@ -0,0 +1,52 @@
+//! A sheet of QR codes, one for each clue, to stick on the items teams look for.
+
+use {
+    super::qr_svg,
+    crate::config::HuntConfig,
+    serde::Serialize,
+    std::path::Path,
+    treasure_hunt_core::clues::{Clues, kind::ClueKind, scan::ScanKey},
+};
+
+#[derive(Debug, Serialize)]
+pub struct HuntScanSheet {
+    pub name: String,
+    pub codes: Vec<ScanCode>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScanCode {
+    pub clue_id: String,
+    pub location: String,
+    /// What to stick the code on, from the clue file, which may contain HTML.
+    pub item: String,
+    pub url: String,
+    pub svg: String,
+}
+
+impl HuntScanSheet {
+    /// Read the hunt's clue file and make the QR codes for its clues. Clues which are
+    /// not answered by typing, e.g. photo clues, have no QR code.
+    pub fn new(hunt: &HuntConfig, key: &ScanKey, base_url: &str) -> anyhow::Result<Self> {
+        let clues = Clues::from_disk(Path::new(&hunt.clues_path))?;
+        let codes = clues
+            .0
+            .iter()
+            .filter(|clue| clue.kind == ClueKind::Text)
+            .map(|clue| {
+                let url = format!("{base_url}/scan/{}", key.token(clue));
+                Ok(ScanCode {
+                    clue_id: clue.id.clone(),
+                    location: clue.location.clone(),
+                    item: clue.item.clone(),
+                    svg: qr_svg(&url)?,
+                    url,
+                })
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self {
+            name: hunt.name.clone(),
+            codes,
+        })
+    }
+}
@@ -1,47 +1,50 @@
 use {
-    crate::{RouteState, routes::TeamData, state::command::Command},
-    axum::{
-        extract::{Path, State},
-        response::Html,
+    crate::{
+        RouteState,
+        routes::{TeamData, auth::Login},
+        state::command::{Command, team_info::TeamInfo},
     },
+    axum::{extract::State, response::Html},
+    minijinja::context,
     std::time::Duration,
     tokio::sync::{mpsc, oneshot},
-    treasure_hunt_core::session::SessionId,
 };
 
-pub async fn action(State(route_state): State<RouteState>, Path(id): Path<String>) -> Html<String> {
-    let team_data = lookup_team_data(&id, route_state.sender).await;
-    let min_skip_duration = Duration::from_secs(route_state.config.min_skip_seconds);
-    let content = fetch_content().replace(
-        "${{MIN_SKIP_DURATION}}",
-        &super::format_duration(min_skip_duration),
-    );
-    super::fill_body(&content, team_data)
-}
-
-#[cfg(feature = "html-reload")]
-fn fetch_content() -> String {
-    let cargo_path = std::path::Path::new(std::env!("CARGO_MANIFEST_DIR"));
-    let path = cargo_path.join("html/about.html");
-    std::fs::read_to_string(path).unwrap()
-}
-
-#[cfg(not(feature = "html-reload"))]
-const fn fetch_content() -> &'static str {
-    include_str!("../../html/about.html")
+pub async fn action(State(route_state): State<RouteState>, login: Option<Login>) -> Html<String> {
+    let team_info = match login {
+        Some(login) => lookup_team_info(login, route_state.sender).await,
+        None => None,
+    };
+    // Teams see the settings of their own hunt.
+    let config = &route_state.config;
+    let min_skip_seconds = team_info
+        .as_ref()
+        .map_or(config.min_skip_seconds, |(_, info)| {
+            config.hunt_settings(info.hunt.as_str()).min_skip_seconds
+        });
+    let (team_data, invite_code, members) = match team_info {
+        Some((team_data, info)) => (Some(team_data), Some(info.invite_code), info.members),
+        None => (None, None, Vec::new()),
+    };
+    let min_skip_duration = Duration::from_secs(min_skip_seconds);
+    let context = context! {
+        min_skip_duration => super::format_duration(min_skip_duration),
+        invite_code,
+        members,
+    };
+    super::render("about.html", context, team_data)
 }
 
-async fn lookup_team_data(maybe_id: &str, sender: mpsc::Sender<Command>) -> Option<TeamData> {
-    let session_id = SessionId::new(maybe_id)?;
+async fn lookup_team_info(
+    login: Login,
+    sender: mpsc::Sender<Command>,
+) -> Option<(TeamData, TeamInfo)> {
     let (tx, rx) = oneshot::channel();
-    let command = Command::GetCurrentClue {
-        id: session_id,
+    let command = Command::TeamInfo {
+        id: login.session_id,
         response: tx,
     };
     sender.send(command).await.ok()?;
-    let (team_name, _) = rx.await.ok()?.ok()?;
-    Some(TeamData {
-        team_name,
-        session_id,
-    })
+    let info = rx.await.ok()?.ok()?;
+    Some((TeamData::new(info.team_name.clone(), &login), info))
 }
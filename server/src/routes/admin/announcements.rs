@@ -0,0 +1,120 @@
+//! Announcements to all teams or to particular teams, e.g. "Pizza is ready!".
+
+use {
+    crate::{RouteState, state::command::Command},
+    axum::{
+        extract::{Form, Path, State},
+        response::Html,
+    },
+    minijinja::context,
+    std::time::{Duration, SystemTime},
+    tokio::sync::oneshot,
+    treasure_hunt_core::session::SessionId,
+};
+
+pub async fn page(
+    State(route_state): State<RouteState>,
+    Path(admin_key): Path<String>,
+) -> Html<String> {
+    super::check_admin_key(&route_state.config, &admin_key)
+        .map(|()| render(None))
+        .unwrap_or_else(crate::routes::error_to_html)
+}
+
+pub async fn action(
+    State(route_state): State<RouteState>,
+    Path(admin_key): Path<String>,
+    Form(input): Form<AnnouncementInput>,
+) -> Html<String> {
+    async fn inner_action(
+        route_state: RouteState,
+        admin_key: &str,
+        input: AnnouncementInput,
+    ) -> anyhow::Result<Html<String>> {
+        super::check_admin_key(&route_state.config, admin_key)?;
+        if let Some(id) = input.remove {
+            let (tx, rx) = oneshot::channel();
+            let command = Command::RemoveAnnouncement { id, response: tx };
+            route_state.sender.send(command).await?;
+            rx.await?;
+            return Ok(render(Some("Announcement removed.")));
+        }
+
+        let message = input.message.unwrap_or_default();
+        let message = message.trim();
+        if message.is_empty() {
+            anyhow::bail!("Write an announcement!");
+        }
+        let expires = match input.expires_minutes.as_deref().map(str::trim) {
+            None | Some("") => None,
+            Some(minutes) => {
+                let minutes: u64 = minutes.parse()?;
+                let expires = minutes
+                    .checked_mul(60)
+                    .and_then(|seconds| SystemTime::now().checked_add(Duration::from_secs(seconds)))
+                    .ok_or_else(|| anyhow::anyhow!("That expiry is too far in the future"))?;
+                Some(expires)
+            }
+        };
+        let teams = input
+            .teams
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(|id| {
+                SessionId::new(&id.to_ascii_uppercase())
+                    .ok_or_else(|| anyhow::anyhow!("Invalid session ID {id:?}"))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let (tx, rx) = oneshot::channel();
+        let command = Command::Announce {
+            message: message.into(),
+            expires,
+            teams,
+            response: tx,
+        };
+        route_state.sender.send(command).await?;
+        rx.await??;
+        Ok(render(Some("Announcement sent.")))
+    }
+    inner_action(route_state, &admin_key, input)
+        .await
+        .unwrap_or_else(crate::routes::error_to_html)
+}
+
+fn render(notice: Option<&str>) -> Html<String> {
+    let announcements: Vec<_> = crate::routes::current_announcements()
+        .into_iter()
+        .map(|announcement| {
+            let teams: Vec<String> = announcement.teams.iter().map(|id| id.to_string()).collect();
+            let expires_in = announcement.expires.map(|expires| {
+                let remaining = expires
+                    .duration_since(SystemTime::now())
+                    .unwrap_or_default();
+                crate::routes::format_duration(remaining)
+            });
+            context! {
+                id => announcement.id,
+                message => announcement.message,
+                teams,
+                expires_in,
+            }
+        })
+        .collect();
+    crate::routes::render(
+        "announcements.html",
+        context! { notice, current => announcements },
+        None,
+    )
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct AnnouncementInput {
+    message: Option<String>,
+    expires_minutes: Option<String>,
+    /// Comma-separated session IDs, or empty for everyone.
+    teams: Option<String>,
+    /// The ID of an announcement to remove instead of making one.
+    remove: Option<u64>,
+}
@@ -0,0 +1,71 @@
+use {
+    crate::{RouteState, state::command::Command},
+    axum::{
+        extract::{Path, State},
+        response::Html,
+    },
+    minijinja::context,
+    tokio::sync::oneshot,
+    treasure_hunt_core::{anomaly::AnomalyKind, session::SessionId},
+};
+
+pub async fn action(
+    State(route_state): State<RouteState>,
+    Path(admin_key): Path<String>,
+) -> Html<String> {
+    async fn inner_anomalies(
+        route_state: RouteState,
+        admin_key: &str,
+    ) -> anyhow::Result<Html<String>> {
+        super::check_admin_key(&route_state.config, admin_key)?;
+        let (tx, rx) = oneshot::channel();
+        let command = Command::AnomalyReport { response: tx };
+        route_state.sender.send(command).await?;
+        let (anomalies, team_names) = rx.await?;
+        let name = |id: &SessionId| {
+            team_names
+                .get(id)
+                .map_or_else(|| id.to_string(), ToString::to_string)
+        };
+
+        let rows: Vec<_> = anomalies
+            .into_iter()
+            .map(|anomaly| {
+                let (signal, details) = match anomaly.kind {
+                    AnomalyKind::FastSolves { count } => (
+                        "Fast solves",
+                        format!("{count} clues solved in under a quarter of the median time"),
+                    ),
+                    AnomalyKind::FollowsTeam { leader, count } => (
+                        "Follows another team",
+                        format!(
+                            "{count} clues solved within 2 minutes of {} solving them",
+                            name(&leader)
+                        ),
+                    ),
+                    AnomalyKind::SharedWrongAnswers { other, answers } => (
+                        "Shared wrong answers",
+                        format!(
+                            "Same wrong answers as {}: {}",
+                            name(&other),
+                            answers.join(", ")
+                        ),
+                    ),
+                };
+                context! {
+                    team_name => name(&anomaly.session_id),
+                    signal,
+                    details,
+                }
+            })
+            .collect();
+        Ok(crate::routes::render(
+            "anomalies.html",
+            context! { rows },
+            None,
+        ))
+    }
+    inner_anomalies(route_state, &admin_key)
+        .await
+        .unwrap_or_else(crate::routes::error_to_html)
+}
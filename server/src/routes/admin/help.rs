@@ -0,0 +1,106 @@
+//! The game master's queue of requests for help from teams.
+
+use {
+    crate::{RouteState, state::command::Command},
+    axum::{
+        extract::{Form, Path, State},
+        response::Html,
+    },
+    minijinja::context,
+    tokio::sync::oneshot,
+    treasure_hunt_core::session::SessionId,
+};
+
+pub async fn page(
+    State(route_state): State<RouteState>,
+    Path(admin_key): Path<String>,
+) -> Html<String> {
+    async fn inner_page(route_state: RouteState, admin_key: &str) -> anyhow::Result<Html<String>> {
+        super::check_admin_key(&route_state.config, admin_key)?;
+        render(&route_state, None).await
+    }
+    inner_page(route_state, &admin_key)
+        .await
+        .unwrap_or_else(crate::routes::error_to_html)
+}
+
+pub async fn reply(
+    State(route_state): State<RouteState>,
+    Path(admin_key): Path<String>,
+    Form(input): Form<ReplyInput>,
+) -> Html<String> {
+    async fn inner_reply(
+        route_state: RouteState,
+        admin_key: &str,
+        input: ReplyInput,
+    ) -> anyhow::Result<Html<String>> {
+        super::check_admin_key(&route_state.config, admin_key)?;
+        let id = SessionId::new(&input.session_id)
+            .ok_or_else(|| anyhow::anyhow!("Invalid session ID"))?;
+        let clue_code: [u8; 32] = hex::decode(&input.clue_code)?
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("Invalid clue code"))?;
+        let message = input.message.trim();
+        if message.is_empty() {
+            anyhow::bail!("Write a reply!");
+        }
+        let points: i32 = match input.points.trim() {
+            "" => 0,
+            points => points.parse()?,
+        };
+        if points < 0 {
+            anyhow::bail!("Points charged cannot be negative");
+        }
+        let (tx, rx) = oneshot::channel();
+        let command = Command::ReplyToHelp {
+            id,
+            clue_code,
+            message: message.into(),
+            points,
+            response: tx,
+        };
+        route_state.sender.send(command).await?;
+        let team_name = rx.await??;
+        let notice = format!("Replied to {team_name}.");
+        render(&route_state, Some(notice)).await
+    }
+    inner_reply(route_state, &admin_key, input)
+        .await
+        .unwrap_or_else(crate::routes::error_to_html)
+}
+
+async fn render(route_state: &RouteState, notice: Option<String>) -> anyhow::Result<Html<String>> {
+    let (tx, rx) = oneshot::channel();
+    let command = Command::HelpQueue { response: tx };
+    route_state.sender.send(command).await?;
+    let requests: Vec<_> = rx
+        .await?
+        .into_iter()
+        .map(|request| {
+            let waiting = request.time.elapsed().unwrap_or_default();
+            context! {
+                session_id => request.session_id.to_string(),
+                team_name => request.team_name,
+                clue_number => request.clue_number,
+                clue_id => request.clue_id,
+                clue_code => hex::encode(request.clue_code),
+                member => request.member,
+                message => request.message,
+                waiting => crate::routes::format_duration(waiting),
+            }
+        })
+        .collect();
+    Ok(crate::routes::render(
+        "help_queue.html",
+        context! { notice, requests },
+        None,
+    ))
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct ReplyInput {
+    session_id: String,
+    clue_code: String,
+    message: String,
+    points: String,
+}
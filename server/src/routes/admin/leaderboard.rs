@@ -0,0 +1,139 @@
+//! Freezing the leaderboard of each hunt and revealing the final standings on a big screen.
+
+use {
+    crate::{
+        RouteState,
+        state::{
+            command::{Command, leader_board::Leaderboard},
+            hunt::HuntId,
+        },
+    },
+    axum::{
+        extract::{Form, Path, State},
+        response::Html,
+    },
+    minijinja::context,
+    tokio::sync::oneshot,
+};
+
+pub async fn page(
+    State(route_state): State<RouteState>,
+    Path(admin_key): Path<String>,
+) -> Html<String> {
+    async fn inner_page(route_state: RouteState, admin_key: &str) -> anyhow::Result<Html<String>> {
+        super::check_admin_key(&route_state.config, admin_key)?;
+        render_page(&route_state, None).await
+    }
+    inner_page(route_state, &admin_key)
+        .await
+        .unwrap_or_else(crate::routes::error_to_html)
+}
+
+pub async fn action(
+    State(route_state): State<RouteState>,
+    Path(admin_key): Path<String>,
+    Form(input): Form<LeaderboardInput>,
+) -> Html<String> {
+    async fn inner_action(
+        route_state: RouteState,
+        admin_key: &str,
+        input: LeaderboardInput,
+    ) -> anyhow::Result<Html<String>> {
+        super::check_admin_key(&route_state.config, admin_key)?;
+        let hunt = input.hunt.as_deref().map(HuntId::new).transpose()?;
+        let freeze = match input.action.as_str() {
+            "freeze" => true,
+            "unfreeze" => false,
+            "reveal" => {
+                let leaderboard = leaderboard(&route_state, hunt, true).await?;
+                return Ok(crate::routes::render(
+                    "leaderboard_reveal.html",
+                    context! {
+                        hunt => leaderboard.hunt,
+                        rows => leaderboard.rows,
+                        is_frozen => leaderboard.frozen_since.is_some(),
+                    },
+                    None,
+                ));
+            }
+            other => anyhow::bail!("Unknown action {other}"),
+        };
+        let (tx, rx) = oneshot::channel();
+        let command = Command::FreezeLeaderboard {
+            hunt,
+            freeze,
+            response: tx,
+        };
+        route_state.sender.send(command).await?;
+        rx.await??;
+        let notice = if freeze {
+            "Teams in that hunt now see the frozen leaderboard."
+        } else {
+            "Teams in that hunt now see the live leaderboard."
+        };
+        render_page(&route_state, Some(notice)).await
+    }
+    inner_action(route_state, &admin_key, input)
+        .await
+        .unwrap_or_else(crate::routes::error_to_html)
+}
+
+/// The leaderboard of `hunt`, or of the default hunt. Its `frozen_since` is set if the
+/// leaderboard is frozen, whether or not the standings are `live`.
+async fn leaderboard(
+    route_state: &RouteState,
+    hunt: Option<HuntId>,
+    live: bool,
+) -> anyhow::Result<Leaderboard> {
+    let (tx, rx) = oneshot::channel();
+    let command = Command::Leaderboard {
+        hunt,
+        maybe_id: None,
+        live,
+        response: tx,
+    };
+    route_state.sender.send(command).await?;
+    Ok(rx.await??)
+}
+
+/// The page listing each hunt with whether its leaderboard is frozen.
+async fn render_page(
+    route_state: &RouteState,
+    notice: Option<&str>,
+) -> anyhow::Result<Html<String>> {
+    let config = &route_state.config;
+    let mut hunts = Vec::new();
+    for hunt in config.hunts() {
+        let leaderboard = leaderboard(route_state, Some(HuntId::new(&hunt.id)?), false).await?;
+        let frozen_for = leaderboard
+            .frozen_since
+            .map(|since| crate::routes::format_duration(since.elapsed().unwrap_or_default()));
+        let freeze_starts = config
+            .hunt_settings(&hunt.id)
+            .end_time
+            .zip(config.leaderboard_freeze_minutes)
+            .map(|(end_time, minutes)| {
+                format!(
+                    "{minutes} minute(s) before the hunt ends at {}",
+                    String::from(end_time)
+                )
+            });
+        hunts.push(context! {
+            id => hunt.id,
+            name => hunt.name,
+            frozen_for,
+            freeze_starts,
+        });
+    }
+    Ok(crate::routes::render(
+        "leaderboard_admin.html",
+        context! { notice, hunts },
+        None,
+    ))
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct LeaderboardInput {
+    action: String,
+    hunt: Option<String>,
+}
@@ -0,0 +1,63 @@
+use {
+    crate::config::Config,
+    axum::{
+        http::header,
+        response::{IntoResponse, Response},
+    },
+    hmac::{Hmac, Mac},
+    sha3::Sha3_256,
+};
+
+pub mod announcements;
+pub mod anomalies;
+pub mod help;
+pub mod leaderboard;
+pub mod photos;
+pub mod pin;
+pub mod print;
+pub mod reload;
+pub mod results;
+pub mod scan_sheet;
+pub mod statistics;
+
+/// Admin pages are only available when an admin key is configured,
+/// and only to requests which present that key.
+fn check_admin_key(config: &Config, admin_key: &str) -> anyhow::Result<()> {
+    match &config.admin_key {
+        Some(expected) if keys_match(expected, admin_key) => Ok(()),
+        _ => anyhow::bail!("Not authorized"),
+    }
+}
+
+/// Compare the keys by their HMACs, which are checked in constant time, so the time
+/// taken does not reveal how much of the key was right.
+fn keys_match(expected: &str, admin_key: &str) -> bool {
+    let mac = |key: &str| {
+        Hmac::<Sha3_256>::new_from_slice(key.as_bytes())
+            .expect("HMAC can take a key of any size")
+            .chain_update(b"admin")
+    };
+    mac(admin_key)
+        .verify_slice(&mac(expected).finalize().into_bytes())
+        .is_ok()
+}
+
+/// A response which browsers save as a file called `filename`.
+fn download(content_type: &'static str, filename: &str, body: String) -> Response {
+    let disposition = format!("attachment; filename=\"{filename}\"");
+    (
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+#[test]
+fn test_keys_match() {
+    assert!(keys_match("adm1n", "adm1n"));
+    assert!(!keys_match("adm1n", "adm1"));
+    assert!(!keys_match("adm1n", ""));
+}
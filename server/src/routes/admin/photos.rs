@@ -0,0 +1,138 @@
+//! The game master's queue of photos submitted for photo clues.
+
+use {
+    crate::{RouteState, routes::photo, state::command::Command},
+    axum::{
+        extract::{Form, Path, State},
+        http::header,
+        response::{Html, IntoResponse, Response},
+    },
+    minijinja::context,
+    tokio::sync::oneshot,
+    treasure_hunt_core::session::SessionId,
+};
+
+pub async fn page(
+    State(route_state): State<RouteState>,
+    Path(admin_key): Path<String>,
+) -> Html<String> {
+    async fn inner_page(route_state: RouteState, admin_key: &str) -> anyhow::Result<Html<String>> {
+        super::check_admin_key(&route_state.config, admin_key)?;
+        render(&route_state, admin_key, None).await
+    }
+    inner_page(route_state, &admin_key)
+        .await
+        .unwrap_or_else(crate::routes::error_to_html)
+}
+
+pub async fn review(
+    State(route_state): State<RouteState>,
+    Path(admin_key): Path<String>,
+    Form(input): Form<ReviewInput>,
+) -> Html<String> {
+    async fn inner_review(
+        route_state: RouteState,
+        admin_key: &str,
+        input: ReviewInput,
+    ) -> anyhow::Result<Html<String>> {
+        super::check_admin_key(&route_state.config, admin_key)?;
+        let id = SessionId::new(&input.session_id)
+            .ok_or_else(|| anyhow::anyhow!("Invalid session ID"))?;
+        let clue_code: [u8; 32] = hex::decode(&input.clue_code)?
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("Invalid clue code"))?;
+        let approved = match input.decision.as_str() {
+            "approve" => true,
+            "reject" => false,
+            _ => anyhow::bail!("Unknown decision"),
+        };
+        let (tx, rx) = oneshot::channel();
+        let command = Command::ReviewPhoto {
+            id,
+            clue_code,
+            approved,
+            response: tx,
+        };
+        route_state.sender.send(command).await?;
+        let team_name = rx.await??;
+        let notice = if approved {
+            format!("Approved the photo from {team_name}.")
+        } else {
+            format!("Rejected the photo from {team_name}.")
+        };
+        render(&route_state, admin_key, Some(notice)).await
+    }
+    inner_review(route_state, &admin_key, input)
+        .await
+        .unwrap_or_else(crate::routes::error_to_html)
+}
+
+/// Serve a submitted photo from the photo directory.
+pub async fn file(
+    State(route_state): State<RouteState>,
+    Path((admin_key, file)): Path<(String, String)>,
+) -> Response {
+    async fn inner_file(
+        route_state: RouteState,
+        admin_key: &str,
+        file: &str,
+    ) -> anyhow::Result<Response> {
+        super::check_admin_key(&route_state.config, admin_key)?;
+        // Photo file names are chosen by the server, so anything else is not a photo.
+        let is_safe = file
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+            && !file.starts_with('.');
+        let content_type = file
+            .rsplit_once('.')
+            .and_then(|(_, extension)| photo::content_type(extension))
+            .filter(|_| is_safe)
+            .ok_or_else(|| anyhow::anyhow!("Not a photo"))?;
+        let path = std::path::Path::new(&route_state.config.photos.dir).join(file);
+        let body = tokio::fs::read(path).await?;
+        Ok(([(header::CONTENT_TYPE, content_type)], body).into_response())
+    }
+    inner_file(route_state, &admin_key, &file)
+        .await
+        .unwrap_or_else(|e| crate::routes::error_to_html(e).into_response())
+}
+
+async fn render(
+    route_state: &RouteState,
+    admin_key: &str,
+    notice: Option<String>,
+) -> anyhow::Result<Html<String>> {
+    let (tx, rx) = oneshot::channel();
+    let command = Command::PhotoQueue { response: tx };
+    route_state.sender.send(command).await?;
+    let photos: Vec<_> = rx
+        .await?
+        .into_iter()
+        .map(|photo| {
+            let waiting = photo.time.elapsed().unwrap_or_default();
+            context! {
+                session_id => photo.session_id.to_string(),
+                team_name => photo.team_name,
+                clue_number => photo.clue_number,
+                clue_id => photo.clue_id,
+                clue_code => hex::encode(photo.clue_code),
+                url => format!("/admin/{admin_key}/photos/{}", photo.file),
+                member => photo.member,
+                waiting => crate::routes::format_duration(waiting),
+            }
+        })
+        .collect();
+    Ok(crate::routes::render(
+        "photo_queue.html",
+        context! { notice, photos },
+        None,
+    ))
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct ReviewInput {
+    session_id: String,
+    clue_code: String,
+    /// Either `approve` or `reject`.
+    decision: String,
+}
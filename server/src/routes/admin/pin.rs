@@ -0,0 +1,66 @@
+use {
+    crate::{
+        RouteState,
+        state::{command::Command, pin::PinHash},
+    },
+    axum::{
+        extract::{Form, Path, State},
+        response::Html,
+    },
+    minijinja::context,
+    tokio::sync::oneshot,
+};
+
+pub async fn form(
+    State(route_state): State<RouteState>,
+    Path(admin_key): Path<String>,
+) -> Html<String> {
+    super::check_admin_key(&route_state.config, &admin_key)
+        .map(|()| render_form(None))
+        .unwrap_or_else(crate::routes::error_to_html)
+}
+
+pub async fn action(
+    State(route_state): State<RouteState>,
+    Path(admin_key): Path<String>,
+    Form(input): Form<ResetPinInput>,
+) -> Html<String> {
+    async fn inner_reset_pin(
+        route_state: RouteState,
+        admin_key: &str,
+        input: ResetPinInput,
+    ) -> anyhow::Result<Html<String>> {
+        super::check_admin_key(&route_state.config, admin_key)?;
+        let pin = match input.pin.trim() {
+            "" => None,
+            pin => Some(PinHash::new(pin)?),
+        };
+        let notice = if pin.is_some() {
+            "PIN changed"
+        } else {
+            "PIN removed"
+        };
+        let (tx, rx) = oneshot::channel();
+        let command = Command::ResetPin {
+            typed_id: input.session_id,
+            pin,
+            response: tx,
+        };
+        route_state.sender.send(command).await?;
+        let team_name = rx.await??;
+        Ok(render_form(Some(format!("{notice} for team {team_name}."))))
+    }
+    inner_reset_pin(route_state, &admin_key, input)
+        .await
+        .unwrap_or_else(crate::routes::error_to_html)
+}
+
+fn render_form(notice: Option<String>) -> Html<String> {
+    crate::routes::render("reset_pin.html", context! { notice }, None)
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct ResetPinInput {
+    session_id: String,
+    pin: String,
+}
@@ -0,0 +1,52 @@
+//! Printable pages for preparing the hunt, made from the clue files.
+
+use {
+    crate::{
+        RouteState,
+        printable::materials::{HuntMaterials, Material},
+        state::command::Command,
+    },
+    axum::{
+        extract::{Path, State},
+        response::Html,
+    },
+    minijinja::context,
+    tokio::sync::oneshot,
+};
+
+/// Show the given `material`, see `Material::parse`.
+pub async fn action(
+    State(route_state): State<RouteState>,
+    Path((admin_key, material)): Path<(String, String)>,
+) -> Html<String> {
+    async fn inner_action(
+        route_state: RouteState,
+        admin_key: &str,
+        material: &str,
+    ) -> anyhow::Result<Html<String>> {
+        super::check_admin_key(&route_state.config, admin_key)?;
+        let material = Material::parse(material)
+            .ok_or_else(|| anyhow::anyhow!("Unknown page, use labels, cheat-sheet or routes"))?;
+        let (tx, rx) = oneshot::channel();
+        let command = Command::Arrangements { response: tx };
+        route_state.sender.send(command).await?;
+        let arrangements = rx.await?;
+        let hunts = route_state
+            .config
+            .hunts()
+            .iter()
+            .map(|hunt| {
+                let arrangements = arrangements
+                    .iter()
+                    .find(|arrangements| arrangements.hunt.as_str() == hunt.id)
+                    .map_or(&[][..], |arrangements| &arrangements.arrangements);
+                HuntMaterials::new(hunt, arrangements)
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let html = crate::routes::render_standalone(material.template(), context! { hunts })?;
+        Ok(Html(html))
+    }
+    inner_action(route_state, &admin_key, &material)
+        .await
+        .unwrap_or_else(crate::routes::error_to_html)
+}
@@ -0,0 +1,89 @@
+use {
+    crate::{
+        RouteState,
+        state::command::{
+            Command,
+            reload::{HuntReload, ReloadError},
+        },
+    },
+    axum::{
+        extract::{Form, Path, State},
+        response::Html,
+    },
+    minijinja::{Value, context},
+    tokio::sync::oneshot,
+};
+
+pub async fn form(
+    State(route_state): State<RouteState>,
+    Path(admin_key): Path<String>,
+) -> Html<String> {
+    super::check_admin_key(&route_state.config, &admin_key)
+        .map(|()| render(None, &[], false))
+        .unwrap_or_else(crate::routes::error_to_html)
+}
+
+pub async fn action(
+    State(route_state): State<RouteState>,
+    Path(admin_key): Path<String>,
+    Form(input): Form<ReloadInput>,
+) -> Html<String> {
+    async fn inner_reload(
+        route_state: RouteState,
+        admin_key: &str,
+        input: ReloadInput,
+    ) -> anyhow::Result<Html<String>> {
+        super::check_admin_key(&route_state.config, admin_key)?;
+        let (tx, rx) = oneshot::channel();
+        let command = Command::ReloadClues {
+            confirm_answer_changes: input.confirm_answer_changes.is_some(),
+            response: tx,
+        };
+        route_state.sender.send(command).await?;
+        let html = match rx.await? {
+            Ok(reloads) => render(Some("Clues reloaded.".into()), &reloads, false),
+            Err(e @ ReloadError::AnswersChanged(_)) => {
+                let notice = format!("{e}. Check the changes below and confirm to apply them.");
+                let ReloadError::AnswersChanged(reloads) = e else {
+                    unreachable!()
+                };
+                render(Some(notice), &reloads, true)
+            }
+            Err(e) => return Err(e.into()),
+        };
+        Ok(html)
+    }
+    inner_reload(route_state, &admin_key, input)
+        .await
+        .unwrap_or_else(crate::routes::error_to_html)
+}
+
+fn render(
+    notice: Option<String>,
+    reloads: &[HuntReload],
+    needs_confirmation: bool,
+) -> Html<String> {
+    let reloads: Vec<Value> = reloads
+        .iter()
+        .map(|reload| {
+            context! {
+                hunt_name => reload.hunt.name,
+                is_unchanged => reload.diff.is_empty(),
+                changed_text => reload.diff.changed_text,
+                changed_answer => reload.diff.changed_answer,
+                removed => reload.diff.removed,
+                added => reload.diff.added,
+            }
+        })
+        .collect();
+    crate::routes::render(
+        "reload.html",
+        context! { notice, reloads, needs_confirmation },
+        None,
+    )
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct ReloadInput {
+    confirm_answer_changes: Option<String>,
+}
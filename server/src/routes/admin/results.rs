@@ -0,0 +1,41 @@
+use {
+    crate::{
+        RouteState,
+        export::results::{Format, Results},
+        state::command::Command,
+    },
+    axum::{
+        extract::{Path, State},
+        response::{IntoResponse, Response},
+    },
+    tokio::sync::oneshot,
+};
+
+/// Download the results of all teams in the given `format`, see `Format::parse`.
+pub async fn action(
+    State(route_state): State<RouteState>,
+    Path((admin_key, format)): Path<(String, String)>,
+) -> Response {
+    async fn inner_results(
+        route_state: RouteState,
+        admin_key: &str,
+        format: &str,
+    ) -> anyhow::Result<Response> {
+        super::check_admin_key(&route_state.config, admin_key)?;
+        let format = Format::parse(format)
+            .ok_or_else(|| anyhow::anyhow!("Unknown format, use json, teams-csv or clues-csv"))?;
+        let (tx, rx) = oneshot::channel();
+        let command = Command::Results { response: tx };
+        route_state.sender.send(command).await?;
+        let results: Results = rx.await?;
+        let body = results.to_format(format)?;
+        Ok(super::download(
+            format.content_type(),
+            format.filename(),
+            body,
+        ))
+    }
+    inner_results(route_state, &admin_key, &format)
+        .await
+        .unwrap_or_else(|e| crate::routes::error_to_html(e).into_response())
+}
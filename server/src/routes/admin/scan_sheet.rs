@@ -0,0 +1,49 @@
+//! A printable sheet of the QR codes teams scan to answer clues.
+
+use {
+    crate::{
+        RouteState,
+        printable::{self, scan_sheet::HuntScanSheet},
+    },
+    axum::{
+        extract::{Path, State},
+        http::{HeaderMap, header},
+        response::Html,
+    },
+    minijinja::context,
+    treasure_hunt_core::clues::scan::ScanKey,
+};
+
+pub async fn page(
+    State(route_state): State<RouteState>,
+    Path(admin_key): Path<String>,
+    headers: HeaderMap,
+) -> Html<String> {
+    fn inner_page(
+        route_state: &RouteState,
+        admin_key: &str,
+        headers: &HeaderMap,
+    ) -> anyhow::Result<Html<String>> {
+        super::check_admin_key(&route_state.config, admin_key)?;
+        let config = &route_state.config;
+        let Some(secret) = &config.scan_secret else {
+            anyhow::bail!("Set scan_secret in the config to use QR codes");
+        };
+        let key = ScanKey::new(secret);
+        let host = headers
+            .get(header::HOST)
+            .and_then(|host| host.to_str().ok());
+        let base_url = printable::base_url(config.public_url.as_deref(), host);
+        let hunts = config
+            .hunts()
+            .iter()
+            .map(|hunt| HuntScanSheet::new(hunt, &key, &base_url))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(crate::routes::render(
+            "scan_sheet.html",
+            context! { hunts },
+            None,
+        ))
+    }
+    inner_page(&route_state, &admin_key, &headers).unwrap_or_else(crate::routes::error_to_html)
+}
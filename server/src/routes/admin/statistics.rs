@@ -0,0 +1,239 @@
+use {
+    crate::{
+        RouteState,
+        state::command::{
+            Command,
+            statistics::{HuntStatistics, StatisticsReport},
+        },
+    },
+    axum::{
+        extract::{Path, State},
+        response::{Html, IntoResponse, Response},
+    },
+    minijinja::context,
+    serde::Serialize,
+    std::time::UNIX_EPOCH,
+    tokio::sync::oneshot,
+    treasure_hunt_core::{
+        analytics::ClueStatistics,
+        session::history::{GuessOutcome, HistoryEvent},
+    },
+};
+
+/// One row per clue, as shown on the statistics page and exported as CSV.
+#[derive(Debug, Serialize)]
+struct ClueRow {
+    hunt: String,
+    clue_id: String,
+    location: String,
+    teams_seen: usize,
+    solved: usize,
+    solve_rate: f64,
+    median_solve_seconds: Option<u64>,
+    hinted: usize,
+    hint_rate: f64,
+    revealed: usize,
+    reveal_rate: f64,
+    skipped: usize,
+    skip_rate: f64,
+    declined: usize,
+    decline_rate: f64,
+    wrong_guesses: usize,
+    common_wrong_answers: String,
+}
+
+impl ClueRow {
+    fn new(hunt: &HuntStatistics, stats: &ClueStatistics) -> Self {
+        let common_wrong_answers = stats
+            .common_wrong_answers
+            .iter()
+            .map(|(answer, teams)| format!("{answer} ({teams})"))
+            .collect::<Vec<_>>()
+            .join("; ");
+        Self {
+            hunt: hunt.hunt.id.to_string(),
+            clue_id: stats.clue_id.clone(),
+            location: stats.location.clone(),
+            teams_seen: stats.teams_seen,
+            solved: stats.solved,
+            solve_rate: stats.solve_rate(),
+            median_solve_seconds: stats.median_solve_duration.map(|d| d.as_secs()),
+            hinted: stats.hinted,
+            hint_rate: stats.hint_rate(),
+            revealed: stats.revealed,
+            reveal_rate: stats.reveal_rate(),
+            skipped: stats.skipped,
+            skip_rate: stats.skip_rate(),
+            declined: stats.declined,
+            decline_rate: stats.decline_rate(),
+            wrong_guesses: stats.wrong_guesses,
+            common_wrong_answers,
+        }
+    }
+}
+
+/// One row per action taken by a team.
+#[derive(Debug, Serialize)]
+struct TimelineRow {
+    hunt: String,
+    team_name: String,
+    session_id: String,
+    /// Seconds since the Unix epoch.
+    time: u64,
+    clue_id: String,
+    member: String,
+    event: &'static str,
+    answer: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Export {
+    clues: Vec<ClueRow>,
+    timelines: Vec<TimelineRow>,
+}
+
+fn clue_rows(report: &StatisticsReport) -> Vec<ClueRow> {
+    report
+        .iter()
+        .flat_map(|hunt| hunt.clues.iter().map(|stats| ClueRow::new(hunt, stats)))
+        .collect()
+}
+
+fn timeline_rows(hunt: &HuntStatistics) -> Vec<TimelineRow> {
+    let mut rows = Vec::new();
+    for timeline in &hunt.timelines {
+        for entry in &timeline.entries {
+            let (event, answer) = match &entry.event {
+                HistoryEvent::Guess { answer, outcome } => {
+                    let event = match outcome {
+                        GuessOutcome::Correct => "Correct answer",
+                        GuessOutcome::OtherClue => "Answer to another clue",
+                        GuessOutcome::Wrong => "Wrong answer",
+                    };
+                    (event, answer.clone())
+                }
+                HistoryEvent::Hint => ("Hint", String::new()),
+                HistoryEvent::Reveal => ("Reveal", String::new()),
+                HistoryEvent::Skip => ("Skip", String::new()),
+                HistoryEvent::HelpRequest { message } => ("Help request", message.clone()),
+                HistoryEvent::HelpReply { message, points } => {
+                    ("Help reply", format!("{message} ({points} points)"))
+                }
+                HistoryEvent::PhotoSubmitted { file } => ("Photo", file.clone()),
+                HistoryEvent::PhotoReviewed { approved: true } => ("Photo approved", String::new()),
+                HistoryEvent::PhotoReviewed { approved: false } => {
+                    ("Photo rejected", String::new())
+                }
+            };
+            rows.push(TimelineRow {
+                hunt: hunt.hunt.id.to_string(),
+                team_name: timeline.team_name.to_string(),
+                session_id: timeline.session_id.to_string(),
+                time: entry
+                    .time
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs(),
+                clue_id: entry.clue_id.clone(),
+                member: entry.member.clone().unwrap_or_default(),
+                event,
+                answer,
+            });
+        }
+    }
+    rows
+}
+
+fn all_timeline_rows(report: &StatisticsReport) -> Vec<TimelineRow> {
+    report.iter().flat_map(timeline_rows).collect()
+}
+
+async fn get_report(route_state: &RouteState, admin_key: &str) -> anyhow::Result<StatisticsReport> {
+    super::check_admin_key(&route_state.config, admin_key)?;
+    let (tx, rx) = oneshot::channel();
+    let command = Command::Statistics { response: tx };
+    route_state.sender.send(command).await?;
+    Ok(rx.await?)
+}
+
+pub async fn page(
+    State(route_state): State<RouteState>,
+    Path(admin_key): Path<String>,
+) -> Html<String> {
+    async fn inner_page(route_state: RouteState, admin_key: &str) -> anyhow::Result<Html<String>> {
+        let report = get_report(&route_state, admin_key).await?;
+        let hunts: Vec<_> = report
+            .iter()
+            .map(|hunt| {
+                let rows: Vec<ClueRow> = hunt
+                    .clues
+                    .iter()
+                    .map(|stats| ClueRow::new(hunt, stats))
+                    .collect();
+                context! {
+                    name => hunt.hunt.name,
+                    n_teams => hunt.timelines.len(),
+                    rows,
+                    timeline => timeline_rows(hunt),
+                }
+            })
+            .collect();
+        Ok(crate::routes::render(
+            "statistics.html",
+            context! { hunts, admin_key },
+            None,
+        ))
+    }
+    inner_page(route_state, &admin_key)
+        .await
+        .unwrap_or_else(crate::routes::error_to_html)
+}
+
+pub async fn json(
+    State(route_state): State<RouteState>,
+    Path(admin_key): Path<String>,
+) -> Response {
+    async fn inner_json(route_state: RouteState, admin_key: &str) -> anyhow::Result<Response> {
+        let report = get_report(&route_state, admin_key).await?;
+        let export = Export {
+            clues: clue_rows(&report),
+            timelines: all_timeline_rows(&report),
+        };
+        let body = serde_json::to_string_pretty(&export)?;
+        Ok(super::download("application/json", "statistics.json", body))
+    }
+    inner_json(route_state, &admin_key)
+        .await
+        .unwrap_or_else(|e| crate::routes::error_to_html(e).into_response())
+}
+
+pub async fn clues_csv(
+    State(route_state): State<RouteState>,
+    Path(admin_key): Path<String>,
+) -> Response {
+    async fn inner_clues_csv(route_state: RouteState, admin_key: &str) -> anyhow::Result<Response> {
+        let report = get_report(&route_state, admin_key).await?;
+        let body = crate::export::to_csv(&clue_rows(&report))?;
+        Ok(super::download("text/csv", "clues.csv", body))
+    }
+    inner_clues_csv(route_state, &admin_key)
+        .await
+        .unwrap_or_else(|e| crate::routes::error_to_html(e).into_response())
+}
+
+pub async fn timelines_csv(
+    State(route_state): State<RouteState>,
+    Path(admin_key): Path<String>,
+) -> Response {
+    async fn inner_timelines_csv(
+        route_state: RouteState,
+        admin_key: &str,
+    ) -> anyhow::Result<Response> {
+        let report = get_report(&route_state, admin_key).await?;
+        let body = crate::export::to_csv(&all_timeline_rows(&report))?;
+        Ok(super::download("text/csv", "timelines.csv", body))
+    }
+    inner_timelines_csv(route_state, &admin_key)
+        .await
+        .unwrap_or_else(|e| crate::routes::error_to_html(e).into_response())
+}
@@ -1,57 +1,102 @@
 use {
     crate::{
         RouteState,
-        routes::clues::{self, construct_clues_form},
-        state::command::Command,
+        routes::{
+            auth::Login,
+            clues::{self, construct_clues_form},
+        },
+        state::command::{
+            Command,
+            answer::{AnswerOutcome, Guess},
+        },
     },
     axum::{
         extract::{Form, Path, State},
         response::Html,
     },
+    minijinja::context,
     tokio::sync::oneshot,
-    treasure_hunt_core::{clues::ClueView, session::SessionId},
+    treasure_hunt_core::clues::ClueView,
 };
 
 pub async fn action(
     State(route_state): State<RouteState>,
-    Path((session_id, clue_id)): Path<(String, String)>,
-    Form(guess): Form<AnswerInput>,
+    login: Login,
+    Path(clue_id): Path<String>,
+    Form(input): Form<AnswerInput>,
 ) -> Html<String> {
-    let logic = |session_id, team_name, mut clue_view: ClueView, route_state: RouteState| async move {
+    let logic = |login: Login, team_name, mut clue_view: ClueView, _, route_state: RouteState| async move {
         let (tx, rx) = oneshot::channel();
         let command = Command::AnswerCurrentClue {
-            id: session_id,
-            guess: guess.clue_answer,
+            id: login.session_id,
+            member: login.member,
+            guess: Guess::Typed(input.clue_answer),
             response: tx,
         };
         route_state.sender.send(command).await?;
-        let points = rx.await??;
+        // The lockout after this answer, which may be the one which caused it.
+        let (points, locked_out) = match rx.await?? {
+            AnswerOutcome::Checked { points, locked_out } => (points, locked_out),
+            // Only scanned QR codes can be for a solved clue.
+            AnswerOutcome::AlreadySolved => (None, None),
+            AnswerOutcome::LockedOut(remaining) => {
+                return Ok(construct_clues_form(
+                    login,
+                    team_name,
+                    clue_view,
+                    Some(remaining),
+                    None,
+                ));
+            }
+        };
         match points {
-            None => {
-                clue_view
-                    .clue
-                    .poem
-                    .push_str("<br><br>That's the wrong answer! Try again.");
-                Ok(construct_clues_form(session_id, team_name, clue_view))
+            None if clue_view
+                .attempts_left
+                .is_some_and(|attempts| attempts <= 1) =>
+            {
+                Ok(super::render(
+                    "no_attempts_left.html",
+                    context! {},
+                    Some(super::TeamData::new(team_name, &login)),
+                ))
             }
-            Some(x) if x >= 0 => Ok(super::fill_body(
-                &correct_answer(session_id),
-                Some(super::TeamData {
+            None => {
+                let notice = match &mut clue_view.attempts_left {
+                    Some(attempts) => {
+                        *attempts -= 1;
+                        format!("That's the wrong answer! Attempts left: {attempts}.")
+                    }
+                    None => String::from("That's the wrong answer! Try again."),
+                };
+                Ok(construct_clues_form(
+                    login,
                     team_name,
-                    session_id,
-                }),
+                    clue_view,
+                    locked_out,
+                    Some(notice),
+                ))
+            }
+            Some(x) if x >= 0 => Ok(super::render(
+                "correct_answer.html",
+                context! {},
+                Some(super::TeamData::new(team_name, &login)),
             )),
             Some(penalty) => {
-                let message = format!(
-                    "<br><br>That answer is correct for <em>some</em> clue, but not <em>this</em> clue. You lose {} points for your error. Try again to find the answer for the current clue.",
+                let notice = format!(
+                    "That answer is correct for some clue, but not this clue. You lose {} points for your error. Try again to find the answer for the current clue.",
                     penalty.abs()
                 );
-                clue_view.clue.poem.push_str(&message);
-                Ok(construct_clues_form(session_id, team_name, clue_view))
+                Ok(construct_clues_form(
+                    login,
+                    team_name,
+                    clue_view,
+                    locked_out,
+                    Some(notice),
+                ))
             }
         }
     };
-    clues::use_current_clue(route_state, &session_id, &clue_id, logic)
+    clues::use_current_clue(route_state, login, &input.csrf_token, &clue_id, logic)
         .await
         .unwrap_or_else(super::error_to_html)
 }
@@ -59,10 +104,5 @@ pub async fn action(
 #[derive(serde::Deserialize, Debug)]
 pub struct AnswerInput {
     clue_answer: String,
-}
-
-fn correct_answer(session_id: SessionId) -> String {
-    format!(
-        r#"<p>Great job! You got the right answer! <a href="/clue/{session_id}">Click here</a> to see the next clue.</p>"#
-    )
+    csrf_token: String,
 }
@@ -0,0 +1,262 @@
+//! Cookie-based login sessions and CSRF protection.
+//!
+//! When a team member registers, joins or logs in, the server sets a session cookie
+//! containing the session ID, member and the team's PIN generation together with an HMAC
+//! of them, so the cookie cannot be forged. Resetting the PIN revokes older cookies. Forms submitted by a logged-in team carry a CSRF token derived from
+//! the same key and the session ID.
+//! Forms shown before logging in use a random token which must match a CSRF cookie.
+
+use {
+    crate::{RouteState, config::Config, state::member::MemberId},
+    axum::{
+        extract::{FromRequestParts, OptionalFromRequestParts},
+        http::{HeaderMap, HeaderValue, header, request::Parts},
+        response::{IntoResponse, Redirect, Response},
+    },
+    hmac::{Hmac, Mac},
+    rand::Rng,
+    sha3::Sha3_256,
+    std::sync::Arc,
+    treasure_hunt_core::session::SessionId,
+};
+
+const SESSION_COOKIE: &str = "trh_session";
+const CSRF_COOKIE: &str = "trh_csrf";
+
+/// Session cookies are kept for a week; long enough for any treasure hunt.
+const SESSION_COOKIE_MAX_AGE: u64 = 7 * 24 * 60 * 60;
+
+#[derive(Clone)]
+pub struct CookieKey(Arc<Vec<u8>>);
+
+impl std::fmt::Debug for CookieKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("CookieKey(..)")
+    }
+}
+
+impl CookieKey {
+    pub fn new(config: &Config) -> Self {
+        let secret = match &config.cookie_secret {
+            Some(secret) => secret.as_bytes().to_vec(),
+            None => {
+                tracing::warn!(
+                    "No cookie secret configured; teams must log in again if the server restarts"
+                );
+                let mut rng = rand::rng();
+                (0..32).map(|_| rng.random()).collect()
+            }
+        };
+        Self(Arc::new(secret))
+    }
+
+    fn mac(&self, purpose: &str, message: &str) -> Hmac<Sha3_256> {
+        let mut mac =
+            Hmac::<Sha3_256>::new_from_slice(&self.0).expect("HMAC can take a key of any size");
+        mac.update(purpose.as_bytes());
+        mac.update(message.as_bytes());
+        mac
+    }
+
+    fn sign(&self, session_id: &SessionId, member: MemberId, pin_generation: u32) -> String {
+        let message = format!("{session_id}.{member}.{pin_generation}");
+        let signature = self.mac("session", &message).finalize().into_bytes();
+        format!("{message}.{}", hex::encode(signature))
+    }
+
+    /// The session ID, member and PIN generation in a signed cookie.
+    fn verify(&self, cookie_value: &str) -> Option<(SessionId, MemberId, u32)> {
+        let (message, signature) = cookie_value.rsplit_once('.')?;
+        let (id, rest) = message.split_once('.')?;
+        // Cookies issued before PINs could be reset have no generation.
+        let (member, pin_generation) = match rest.split_once('.') {
+            Some((member, pin_generation)) => (member, pin_generation.parse().ok()?),
+            None => (rest, 0),
+        };
+        let session_id = SessionId::new(id)?;
+        let member = member.parse().ok()?;
+        let signature = hex::decode(signature).ok()?;
+        self.mac("session", message)
+            .verify_slice(&signature)
+            .ok()
+            .map(|_| (session_id, member, pin_generation))
+    }
+
+    pub fn csrf_token(&self, session_id: &SessionId) -> String {
+        let message = session_id.to_string();
+        hex::encode(self.mac("csrf", &message).finalize().into_bytes())
+    }
+}
+
+/// A team member who is logged in via the session cookie.
+#[derive(Debug, Clone)]
+pub struct Login {
+    pub session_id: SessionId,
+    pub member: MemberId,
+    pub csrf_token: String,
+}
+
+impl Login {
+    pub fn new(key: &CookieKey, session_id: SessionId, member: MemberId) -> Self {
+        Self {
+            csrf_token: key.csrf_token(&session_id),
+            session_id,
+            member,
+        }
+    }
+
+    /// Check the CSRF token submitted with a form.
+    pub fn check_csrf(&self, submitted_token: &str) -> anyhow::Result<()> {
+        if submitted_token != self.csrf_token {
+            anyhow::bail!("Invalid form submission. Reload the page and try again.");
+        }
+        Ok(())
+    }
+
+    /// The `Set-Cookie` header value which logs in this team, until its PIN is next reset.
+    pub fn cookie(&self, key: &CookieKey, pin_generation: u32) -> HeaderValue {
+        let value = format!(
+            "{SESSION_COOKIE}={}; Path=/; Max-Age={SESSION_COOKIE_MAX_AGE}; HttpOnly; SameSite=Lax",
+            key.sign(&self.session_id, self.member, pin_generation)
+        );
+        HeaderValue::from_str(&value).expect("Cookie is valid header value")
+    }
+}
+
+/// The `Set-Cookie` header value which logs out a team.
+pub fn logout_cookie() -> HeaderValue {
+    HeaderValue::from_static("trh_session=; Path=/; Max-Age=0; HttpOnly; SameSite=Lax")
+}
+
+impl OptionalFromRequestParts<RouteState> for Login {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &RouteState,
+    ) -> Result<Option<Self>, Self::Rejection> {
+        let login = get_cookie(&parts.headers, SESSION_COOKIE)
+            .and_then(|value| state.cookie_key.verify(value))
+            .filter(|(session_id, _, pin_generation)| {
+                let generations = state.pin_generations.borrow();
+                generations.get(session_id).copied().unwrap_or(0) == *pin_generation
+            })
+            .map(|(session_id, member, _)| Self::new(&state.cookie_key, session_id, member));
+        Ok(login)
+    }
+}
+
+impl FromRequestParts<RouteState> for Login {
+    type Rejection = Response;
+
+    /// Teams which are not logged in are sent to the registration page.
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &RouteState,
+    ) -> Result<Self, Self::Rejection> {
+        let login =
+            <Self as OptionalFromRequestParts<RouteState>>::from_request_parts(parts, state)
+                .await
+                .unwrap_or_else(|never| match never {});
+        login.ok_or_else(|| Redirect::to("/").into_response())
+    }
+}
+
+/// CSRF protection for forms shown before a team is logged in. The token is
+/// stored in a cookie and must also be submitted with the form.
+#[derive(Debug, Clone)]
+pub struct AnonymousCsrf {
+    pub token: String,
+    is_new: bool,
+}
+
+impl AnonymousCsrf {
+    /// Check the CSRF token submitted with a form.
+    pub fn check(&self, submitted_token: &str) -> anyhow::Result<()> {
+        if self.is_new || submitted_token != self.token {
+            anyhow::bail!("Invalid form submission. Reload the page and try again.");
+        }
+        Ok(())
+    }
+
+    /// The `Set-Cookie` header value to store a newly generated token.
+    pub fn cookie(&self) -> Option<HeaderValue> {
+        if !self.is_new {
+            return None;
+        }
+        let value = format!(
+            "{CSRF_COOKIE}={}; Path=/; HttpOnly; SameSite=Lax",
+            self.token
+        );
+        HeaderValue::from_str(&value).ok()
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for AnonymousCsrf {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let csrf = match get_cookie(&parts.headers, CSRF_COOKIE) {
+            Some(token) => Self {
+                token: token.into(),
+                is_new: false,
+            },
+            None => {
+                let bytes: [u8; 16] = rand::rng().random();
+                Self {
+                    token: hex::encode(bytes),
+                    is_new: true,
+                }
+            }
+        };
+        Ok(csrf)
+    }
+}
+
+fn get_cookie<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find_map(|(key, value)| (key == name).then_some(value))
+}
+
+#[test]
+fn test_session_cookie() {
+    let key = CookieKey(Arc::new(b"secret".to_vec()));
+    let session_id = SessionId::new("ABCD").unwrap();
+    let signed = key.sign(&session_id, 1, 2);
+    assert_eq!(key.verify(&signed), Some((session_id, 1, 2)));
+
+    let other_key = CookieKey(Arc::new(b"other".to_vec()));
+    assert_eq!(other_key.verify(&signed), None, "Cookie must be signed");
+    let forged = signed.replacen("ABCD", "ABCE", 1);
+    assert_eq!(key.verify(&forged), None, "Cookie cannot be altered");
+    let forged = signed.replacen(".1.", ".0.", 1);
+    assert_eq!(key.verify(&forged), None, "Member cannot be altered");
+    let forged = signed.replacen(".2.", ".3.", 1);
+    assert_eq!(
+        key.verify(&forged),
+        None,
+        "PIN generation cannot be altered"
+    );
+
+    let message = format!("{session_id}.1");
+    let signature = key.mac("session", &message).finalize().into_bytes();
+    let legacy = format!("{message}.{}", hex::encode(signature));
+    assert_eq!(
+        key.verify(&legacy),
+        Some((session_id, 1, 0)),
+        "Cookies without a PIN generation have generation 0"
+    );
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::COOKIE,
+        HeaderValue::from_str(&format!("a=b; {SESSION_COOKIE}={signed}")).unwrap(),
+    );
+    assert_eq!(get_cookie(&headers, SESSION_COOKIE), Some(signed.as_str()));
+    assert_eq!(get_cookie(&headers, CSRF_COOKIE), None);
+}
@@ -0,0 +1,60 @@
+//! The team card: a printable page with the QR code teammates scan to join the team.
+
+use {
+    crate::{RouteState, config::Config, printable, routes::auth::Login, state::command::Command},
+    axum::{
+        extract::State,
+        http::{HeaderMap, header},
+        response::Html,
+    },
+    minijinja::context,
+    tokio::sync::oneshot,
+};
+
+pub async fn action(
+    State(route_state): State<RouteState>,
+    login: Login,
+    headers: HeaderMap,
+) -> Html<String> {
+    async fn inner_action(
+        route_state: RouteState,
+        login: Login,
+        headers: HeaderMap,
+    ) -> anyhow::Result<Html<String>> {
+        let (tx, rx) = oneshot::channel();
+        let command = Command::TeamInfo {
+            id: login.session_id,
+            response: tx,
+        };
+        route_state.sender.send(command).await?;
+        let info = rx.await??;
+        let (join_url, join_qr) = join_qr(&route_state.config, &headers, &info.invite_code)?;
+        let context = context! {
+            invite_code => info.invite_code,
+            join_url,
+            join_qr,
+        };
+        let team_data = super::TeamData::new(info.team_name, &login);
+        Ok(super::render("team_card.html", context, Some(team_data)))
+    }
+
+    inner_action(route_state, login, headers)
+        .await
+        .unwrap_or_else(super::error_to_html)
+}
+
+/// The link which opens the form to join the team with the given invite code, and
+/// a QR code of it as SVG.
+pub fn join_qr(
+    config: &Config,
+    headers: &HeaderMap,
+    invite_code: &str,
+) -> anyhow::Result<(String, String)> {
+    let host = headers
+        .get(header::HOST)
+        .and_then(|host| host.to_str().ok());
+    let base_url = printable::base_url(config.public_url.as_deref(), host);
+    let url = format!("{base_url}/join/{invite_code}");
+    let svg = printable::qr_svg(&url)?;
+    Ok((url, svg))
+}
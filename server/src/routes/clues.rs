@@ -1,48 +1,50 @@
 use {
     crate::{
         RouteState,
+        config::HuntSettings,
+        routes::{auth::Login, scan::Scanned},
         state::{
             TeamName,
             command::{Command, Either},
         },
     },
     axum::{
-        extract::{Path, State},
+        extract::{Query, State},
         response::Html,
     },
+    minijinja::context,
+    std::time::Duration,
     tokio::sync::{mpsc, oneshot},
-    treasure_hunt_core::{
-        clues::{ClueView, status::KnowledgeKind},
-        session::SessionId,
-    },
+    treasure_hunt_core::clues::{ClueView, kind::ClueKind, status::KnowledgeKind},
 };
 
+/// Run the `logic` for a form submitted on the page of the clue with ID `clue_id`,
+/// provided the CSRF token is valid and that clue is still the team's current clue.
 pub async fn use_current_clue<G, F>(
     route_state: RouteState,
-    session_id: &str,
+    login: Login,
+    csrf_token: &str,
     clue_id: &str,
     logic: G,
 ) -> anyhow::Result<Html<String>>
 where
     F: Future<Output = anyhow::Result<Html<String>>>,
-    G: FnOnce(SessionId, TeamName, ClueView, RouteState) -> F,
+    G: FnOnce(Login, TeamName, ClueView, Option<Duration>, RouteState) -> F,
 {
-    let Some(session_id) = SessionId::new(session_id) else {
-        anyhow::bail!("Invalid session ID");
-    };
+    login.check_csrf(csrf_token)?;
 
     // Look up current clue
     let (tx, rx) = oneshot::channel();
     let command = Command::GetCurrentClue {
-        id: session_id,
+        id: login.session_id,
         response: tx,
     };
     route_state.sender.send(command).await?;
     let (team_name, maybe_clue) = rx.await??;
-    let clue_view = match maybe_clue {
-        Either::Left(clue_view) => clue_view,
+    let (clue_view, locked_out) = match maybe_clue {
+        Either::Left(current) => current,
         Either::Right(score) => {
-            return Ok(no_more_clues(session_id, team_name, score));
+            return Ok(no_more_clues(login, team_name, score));
         }
     };
 
@@ -50,53 +52,101 @@ where
     // requested for then the request is invalid and we just show
     // the normal clues page.
     if hex::encode(clue_view.clue.code) != clue_id {
-        return Ok(construct_clues_form(session_id, team_name, clue_view));
+        return Ok(construct_clues_form(
+            login, team_name, clue_view, locked_out, None,
+        ));
     }
 
-    logic(session_id, team_name, clue_view, route_state).await
+    logic(login, team_name, clue_view, locked_out, route_state).await
+}
+
+/// The query of the clue page, which says what happened when the team scanned a QR code.
+#[derive(serde::Deserialize, Debug, Default)]
+pub struct ClueQuery {
+    scanned: Option<Scanned>,
 }
 
-pub async fn form(State(route_state): State<RouteState>, Path(id): Path<String>) -> Html<String> {
+/// The settings of the hunt which the logged-in team is playing.
+pub async fn hunt_settings(
+    route_state: &RouteState,
+    login: &Login,
+) -> anyhow::Result<HuntSettings> {
+    let (tx, rx) = oneshot::channel();
+    let command = Command::TeamInfo {
+        id: login.session_id,
+        response: tx,
+    };
+    route_state.sender.send(command).await?;
+    let hunt = rx.await??.hunt;
+    Ok(route_state.config.hunt_settings(hunt.as_str()))
+}
+
+pub async fn form(
+    State(route_state): State<RouteState>,
+    login: Login,
+    Query(query): Query<ClueQuery>,
+) -> Html<String> {
     async fn inner_clues_form(
         sender: mpsc::Sender<Command>,
-        id: &str,
+        login: Login,
+        notice: Option<String>,
     ) -> anyhow::Result<Html<String>> {
-        let Some(session_id) = SessionId::new(id) else {
-            // If the session is invalid then return the registration page instead.
-            return Ok(crate::routes::register::form().await);
-        };
         let (tx, rx) = oneshot::channel();
         let command = Command::GetCurrentClue {
-            id: session_id,
+            id: login.session_id,
             response: tx,
         };
         sender.send(command).await?;
         let (team_name, maybe_clue) = rx.await??;
-        let clue_view = match maybe_clue {
-            Either::Left(clue_view) => clue_view,
+        let (clue_view, locked_out) = match maybe_clue {
+            Either::Left(current) => current,
             Either::Right(score) => {
-                return Ok(no_more_clues(session_id, team_name, score));
+                return Ok(no_more_clues(login, team_name, score));
             }
         };
-        Ok(construct_clues_form(session_id, team_name, clue_view))
+        Ok(construct_clues_form(
+            login, team_name, clue_view, locked_out, notice,
+        ))
     }
 
-    inner_clues_form(route_state.sender, &id)
+    let notice = query.scanned.map(Scanned::notice);
+    inner_clues_form(route_state.sender, login, notice)
         .await
         .unwrap_or_else(super::error_to_html)
 }
 
+/// The form data submitted by the buttons on the clue page which have no other inputs.
+#[derive(serde::Deserialize, Debug)]
+pub struct CsrfInput {
+    pub csrf_token: String,
+}
+
+/// Render the page for the current clue. The `notice` is shown below the poem, e.g. to
+/// explain why a request was not carried out, and `locked_out` is how long the team must
+/// wait before answering after too many guesses.
 pub fn construct_clues_form(
-    session_id: SessionId,
+    login: Login,
     team_name: TeamName,
     clue_view: ClueView,
+    locked_out: Option<Duration>,
+    notice: Option<String>,
 ) -> Html<String> {
     let clue = clue_view.clue;
+    let is_photo = clue.kind == ClueKind::Photo;
+    let answer = match &clue.kind {
+        ClueKind::Text | ClueKind::Photo => context! {},
+        ClueKind::MultipleChoice { options, .. } => context! {
+            options,
+            attempts_left => clue_view.attempts_left,
+        },
+        ClueKind::Numeric { .. } => context! { is_numeric => true },
+        ClueKind::Sequence { items } => context! { sequence => items },
+    };
     let knowledge = clue_view.knowledge;
     let (hint_url, hint_button_text) = match knowledge {
-        KnowledgeKind::Unaided => ("hint", "Ask for a hint"),
-        KnowledgeKind::WithHint => ("reveal", "Reveal the item to find"),
-        KnowledgeKind::KnowingItem => ("null", "NULL"),
+        KnowledgeKind::Unaided => (Some("hint"), "Ask for a hint"),
+        KnowledgeKind::WithHint => (Some("reveal"), "Reveal the item to find"),
+        KnowledgeKind::KnowingItem => (None, "NULL"),
     };
     let skip_text = if clue_view.is_previously_skipped {
         "Skip forever"
@@ -104,59 +154,57 @@ pub fn construct_clues_form(
         "Skip for now"
     };
 
-    let mut html_body = format!("<p>{}</p><br><br>\n", clue.poem);
-
-    if matches!(
-        knowledge,
-        KnowledgeKind::WithHint | KnowledgeKind::KnowingItem
-    ) {
-        html_body.push_str(&format!("Hint: <p>{}</p><br><br>\n", clue.hint));
-    }
-
-    if matches!(knowledge, KnowledgeKind::KnowingItem) {
-        html_body.push_str(&format!("Item to find: <p>{}</p><br><br>\n", clue.item));
-    }
-
-    if matches!(knowledge, KnowledgeKind::Unaided | KnowledgeKind::WithHint) {
-        html_body.push_str(include_str!("../../html/hint_form.html"));
-    }
-
-    html_body.push_str(include_str!("../../html/answer_form.html"));
-    html_body.push_str(include_str!("../../html/skip_form.html"));
-
-    let html_body = html_body
-        .replace("${{CLUE_ID}}", &hex::encode(clue.code))
-        .replace("${{HINT_BASE_URL}}", hint_url)
-        .replace("${{HINT_BUTTON_TEXT}}", hint_button_text)
-        .replace("${{SKIP_BUTTON_TEXT}}", skip_text);
-
-    let team_data = super::TeamData {
-        team_name,
-        session_id,
+    // The clue text comes from the organisers' clue file, so it is trusted to contain HTML.
+    let context = context! {
+        clue => context! {
+            poem => clue.poem,
+            hint => clue.hint,
+            item => clue.item,
+        },
+        clue_id => hex::encode(clue.code),
+        number => clue_view.number,
+        notice,
+        locked_out => locked_out.map(super::format_duration),
+        is_photo,
+        answer,
+        show_hint => matches!(knowledge, KnowledgeKind::WithHint | KnowledgeKind::KnowingItem),
+        show_item => matches!(knowledge, KnowledgeKind::KnowingItem),
+        hint_url,
+        hint_button_text,
+        skip_text,
+        help_replies => clue_view.help_replies,
+        awaiting_help => clue_view.awaiting_help,
     };
-    super::fill_body(&html_body, Some(team_data))
+
+    let team_data = super::TeamData::new(team_name, &login);
+    super::render("clue.html", context, Some(team_data))
 }
 
-pub fn no_more_clues(session_id: SessionId, team_name: TeamName, score: i32) -> Html<String> {
-    let team_data = super::TeamData {
-        team_name,
-        session_id,
-    };
-    let content = include_str!("../../html/complete.html")
-        .replace("${{SCORE}}", &score.to_string())
-        .replace("${{TEAM_NAME}}", &team_data.team_name.to_string());
-    super::fill_body(&content, Some(team_data))
+pub fn no_more_clues(login: Login, team_name: TeamName, score: i32) -> Html<String> {
+    let team_data = super::TeamData::new(team_name, &login);
+    super::render("complete.html", context! { score }, Some(team_data))
 }
 
 #[test]
 fn test_construct_clues_form() {
     let team_name = TeamName::new("Michael").unwrap();
-    let session_id = SessionId::random();
+    let login = Login {
+        session_id: treasure_hunt_core::session::SessionId::new("ABCD").unwrap(),
+        member: 0,
+        csrf_token: String::from("token"),
+    };
     let clue = treasure_hunt_core::clues::Clue::mock(1, "A");
     let duration = std::time::Duration::from_secs(0);
-    let mut clue_view = ClueView::new(clue, KnowledgeKind::Unaided, false, duration);
+    let mut clue_view = ClueView::new(clue, 1, KnowledgeKind::Unaided, false, duration);
 
-    let text = construct_clues_form(session_id, team_name.clone(), clue_view.clone()).0;
+    let text = construct_clues_form(
+        login.clone(),
+        team_name.clone(),
+        clue_view.clone(),
+        None,
+        None,
+    )
+    .0;
     assert!(
         !text.contains("Hint: <p>"),
         "Hint is NOT present in unaided clue"
@@ -176,7 +224,14 @@ fn test_construct_clues_form() {
     );
 
     clue_view.hinted();
-    let text = construct_clues_form(session_id, team_name.clone(), clue_view.clone()).0;
+    let text = construct_clues_form(
+        login.clone(),
+        team_name.clone(),
+        clue_view.clone(),
+        None,
+        None,
+    )
+    .0;
     assert!(text.contains("Hint: <p>"), "Hint is present in hinted clue");
     assert!(
         !text.contains("Item to find: <p>"),
@@ -193,7 +248,14 @@ fn test_construct_clues_form() {
     );
 
     clue_view.revealed();
-    let text = construct_clues_form(session_id, team_name.clone(), clue_view.clone()).0;
+    let text = construct_clues_form(
+        login.clone(),
+        team_name.clone(),
+        clue_view.clone(),
+        None,
+        None,
+    )
+    .0;
     assert!(
         text.contains("Hint: <p>"),
         "Hint is present in revealed clue"
@@ -212,9 +274,117 @@ fn test_construct_clues_form() {
     );
 
     clue_view.is_previously_skipped = true;
-    let text = construct_clues_form(session_id, team_name.clone(), clue_view.clone()).0;
+    let text = construct_clues_form(
+        login.clone(),
+        team_name.clone(),
+        clue_view.clone(),
+        None,
+        None,
+    )
+    .0;
     assert!(
         text.contains(r#"<input type="submit" value="Skip forever">"#),
         "skip forever button is present for previously skipped clue"
     );
+
+    let team_name = TeamName::new("<script>alert(1)</script>").unwrap();
+    let notice = Some(String::from("<b>Wait</b>"));
+    let text = construct_clues_form(login.clone(), team_name, clue_view.clone(), None, notice).0;
+    assert!(
+        !text.contains("<script>alert") && text.contains("&lt;script&gt;alert"),
+        "Team names are escaped"
+    );
+    assert!(text.contains("&lt;b&gt;Wait"), "Notices are escaped");
+    assert!(
+        text.contains("<form action=\"/help/") && !text.contains("The game master says"),
+        "Help can be requested"
+    );
+
+    clue_view.help_replies = vec![String::from("Look <up>")];
+    let text = construct_clues_form(
+        login.clone(),
+        TeamName::new("Michael").unwrap(),
+        clue_view.clone(),
+        None,
+        None,
+    )
+    .0;
+    assert!(
+        text.contains("The game master says: <strong>Look &lt;up&gt;</strong>"),
+        "Replies are shown, escaped"
+    );
+
+    let text = construct_clues_form(
+        login.clone(),
+        TeamName::new("Michael").unwrap(),
+        clue_view.clone(),
+        Some(Duration::from_secs(90)),
+        None,
+    )
+    .0;
+    assert!(
+        text.contains("Wait 1 minute before answering again"),
+        "The remaining lockout is shown"
+    );
+
+    clue_view.clue.kind = ClueKind::MultipleChoice {
+        options: vec![String::from("<b>Red</b>"), String::from("Blue")],
+        max_attempts: 2,
+    };
+    clue_view.attempts_left = Some(2);
+    let text = construct_clues_form(
+        login.clone(),
+        TeamName::new("Michael").unwrap(),
+        clue_view.clone(),
+        None,
+        None,
+    )
+    .0;
+    assert!(
+        text.contains(r#"<input type="radio" name="clue_answer" value="Blue" required>"#)
+            && text.contains("&lt;b&gt;Red")
+            && text.contains("Attempts left: 2"),
+        "Options are shown as radio buttons, escaped"
+    );
+
+    clue_view.clue.kind = ClueKind::Numeric {
+        value: 4.0,
+        tolerance: 0.5,
+    };
+    let text = construct_clues_form(
+        login.clone(),
+        TeamName::new("Michael").unwrap(),
+        clue_view.clone(),
+        None,
+        None,
+    )
+    .0;
+    assert!(text.contains(r#"<input type="number" step="any""#));
+
+    clue_view.clue.kind = ClueKind::Sequence {
+        items: vec![String::from("Second"), String::from("First")],
+    };
+    let text = construct_clues_form(
+        login.clone(),
+        TeamName::new("Michael").unwrap(),
+        clue_view.clone(),
+        None,
+        None,
+    )
+    .0;
+    assert!(text.contains("<li><span>Second</span>"));
+
+    clue_view.clue.kind = ClueKind::Photo;
+    let text = construct_clues_form(
+        login,
+        TeamName::new("Michael").unwrap(),
+        clue_view,
+        None,
+        None,
+    )
+    .0;
+    assert!(
+        text.contains("<form action=\"/photo/") && !text.contains("<form action=\"/answer/"),
+        "Photo clues are answered by uploading a photo"
+    );
 }
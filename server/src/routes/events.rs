@@ -0,0 +1,27 @@
+use {
+    crate::{RouteState, routes::auth::Login},
+    axum::{
+        extract::State,
+        response::sse::{Event, KeepAlive, Sse},
+    },
+    std::convert::Infallible,
+    tokio_stream::{Stream, StreamExt, wrappers::BroadcastStream},
+};
+
+/// A stream of what the other members of the team are doing, for open clue pages.
+pub async fn action(
+    State(route_state): State<RouteState>,
+    login: Login,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let events = BroadcastStream::new(route_state.events.subscribe());
+    let stream = events.filter_map(move |event| {
+        // Lagging behind only means missing some events; the page reloads anyway.
+        let event = event.ok()?;
+        let is_for_team = event
+            .session_id
+            .is_none_or(|session_id| session_id == login.session_id);
+        let is_teammate = is_for_team && event.member != Some(login.member);
+        is_teammate.then(|| Ok(Event::default().data(event.message)))
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
@@ -0,0 +1,66 @@
+use {
+    crate::{
+        RouteState,
+        routes::{auth::Login, clues},
+        state::command::Command,
+    },
+    axum::{
+        extract::{Form, Path, State},
+        response::Html,
+    },
+    tokio::sync::oneshot,
+};
+
+/// Longest request for help a team can send, in bytes.
+const MAX_HELP_MESSAGE_LEN: usize = 500;
+
+/// Send a request for help with the current clue to the game master.
+pub async fn action(
+    State(route_state): State<RouteState>,
+    login: Login,
+    Path(clue_id): Path<String>,
+    Form(input): Form<HelpInput>,
+) -> Html<String> {
+    let csrf_token = input.csrf_token.clone();
+    clues::use_current_clue(
+        route_state,
+        login,
+        &csrf_token,
+        &clue_id,
+        |login, team_name, mut clue_view, locked_out, route_state| async move {
+            let message = input.message.trim();
+            let notice = if message.is_empty() {
+                "Write what you need help with."
+            } else if message.len() > MAX_HELP_MESSAGE_LEN {
+                "That message is too long!"
+            } else {
+                let (tx, rx) = oneshot::channel();
+                let command = Command::RequestHelp {
+                    id: login.session_id,
+                    member: login.member,
+                    message: message.into(),
+                    response: tx,
+                };
+                route_state.sender.send(command).await?;
+                rx.await??;
+                clue_view.awaiting_help = true;
+                "Your message was sent to the game master."
+            };
+            Ok(clues::construct_clues_form(
+                login,
+                team_name,
+                clue_view,
+                locked_out,
+                Some(notice.into()),
+            ))
+        },
+    )
+    .await
+    .unwrap_or_else(super::error_to_html)
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct HelpInput {
+    csrf_token: String,
+    message: String,
+}
@@ -1,42 +1,59 @@
 use {
     crate::{
         RouteState,
-        routes::clues::{self, construct_clues_form},
+        routes::{
+            auth::Login,
+            clues::{self, CsrfInput, construct_clues_form},
+        },
         state::{TeamName, command::Command},
     },
     axum::{
-        extract::{Path, State},
+        extract::{Form, Path, State},
         response::Html,
     },
     std::time::Duration,
-    treasure_hunt_core::{
-        clues::{ClueView, status::KnowledgeKind},
-        session::SessionId,
-    },
+    treasure_hunt_core::clues::{ClueView, status::KnowledgeKind},
 };
 
 pub async fn hint_action(
     State(route_state): State<RouteState>,
-    Path((session_id, clue_id)): Path<(String, String)>,
+    login: Login,
+    Path(clue_id): Path<String>,
+    Form(input): Form<CsrfInput>,
 ) -> Html<String> {
-    clues::use_current_clue(route_state, &session_id, &clue_id, update_with_hint)
-        .await
-        .unwrap_or_else(super::error_to_html)
+    clues::use_current_clue(
+        route_state,
+        login,
+        &input.csrf_token,
+        &clue_id,
+        update_with_hint,
+    )
+    .await
+    .unwrap_or_else(super::error_to_html)
 }
 
 pub async fn reveal_action(
     State(route_state): State<RouteState>,
-    Path((session_id, clue_id)): Path<(String, String)>,
+    login: Login,
+    Path(clue_id): Path<String>,
+    Form(input): Form<CsrfInput>,
 ) -> Html<String> {
-    clues::use_current_clue(route_state, &session_id, &clue_id, update_with_item)
-        .await
-        .unwrap_or_else(super::error_to_html)
+    clues::use_current_clue(
+        route_state,
+        login,
+        &input.csrf_token,
+        &clue_id,
+        update_with_item,
+    )
+    .await
+    .unwrap_or_else(super::error_to_html)
 }
 
 async fn update_with_hint(
-    session_id: SessionId,
+    login: Login,
     team_name: TeamName,
     mut clue_view: ClueView,
+    locked_out: Option<Duration>,
     route_state: RouteState,
 ) -> anyhow::Result<Html<String>> {
     // If the current clue does not have the expected level of knowledge
@@ -44,34 +61,45 @@ async fn update_with_hint(
     // (e.g. from a page reload).
     if !matches!(clue_view.knowledge, KnowledgeKind::Unaided) {
         return Ok(clues::construct_clues_form(
-            session_id, team_name, clue_view,
+            login, team_name, clue_view, locked_out, None,
         ));
     }
 
     // Require waiting some time before giving a hint
-    let min_hint_duration = Duration::from_secs(route_state.config.min_hint_seconds);
+    let settings = clues::hunt_settings(&route_state, &login).await?;
+    let min_hint_duration = Duration::from_secs(settings.min_hint_seconds);
     if clue_view.duration < min_hint_duration {
         let time_to_hint = min_hint_duration.saturating_sub(clue_view.duration);
-        clue_view.clue.poem.push_str(&format!(
-            "<br><br>Wait at least {} for a hint.",
+        let notice = format!(
+            "Wait at least {} for a hint.",
             super::format_duration(time_to_hint)
+        );
+        return Ok(construct_clues_form(
+            login,
+            team_name,
+            clue_view,
+            locked_out,
+            Some(notice),
         ));
-        return Ok(construct_clues_form(session_id, team_name, clue_view));
     }
 
     // Mark clue as hinted
-    let command = Command::HintCurrentClue { id: session_id };
+    let command = Command::HintCurrentClue {
+        id: login.session_id,
+        member: login.member,
+    };
     route_state.sender.send(command).await?;
     clue_view.hinted();
     Ok(clues::construct_clues_form(
-        session_id, team_name, clue_view,
+        login, team_name, clue_view, locked_out, None,
     ))
 }
 
 async fn update_with_item(
-    session_id: SessionId,
+    login: Login,
     team_name: TeamName,
     mut clue_view: ClueView,
+    locked_out: Option<Duration>,
     route_state: RouteState,
 ) -> anyhow::Result<Html<String>> {
     // If the current clue does not have the expected level of knowledge
@@ -79,26 +107,36 @@ async fn update_with_item(
     // (e.g. from a page reload).
     if !matches!(clue_view.knowledge, KnowledgeKind::WithHint) {
         return Ok(clues::construct_clues_form(
-            session_id, team_name, clue_view,
+            login, team_name, clue_view, locked_out, None,
         ));
     }
 
     // Require waiting some time before revealing the item
-    let min_reveal_duration = Duration::from_secs(route_state.config.min_reveal_seconds);
+    let settings = clues::hunt_settings(&route_state, &login).await?;
+    let min_reveal_duration = Duration::from_secs(settings.min_reveal_seconds);
     if clue_view.duration < min_reveal_duration {
         let time_to_hint = min_reveal_duration.saturating_sub(clue_view.duration);
-        clue_view.clue.hint.push_str(&format!(
-            "<br><br>Wait at least {} for revealing the item.",
+        let notice = format!(
+            "Wait at least {} for revealing the item.",
             super::format_duration(time_to_hint)
+        );
+        return Ok(construct_clues_form(
+            login,
+            team_name,
+            clue_view,
+            locked_out,
+            Some(notice),
         ));
-        return Ok(construct_clues_form(session_id, team_name, clue_view));
     }
 
     // Mark clue as revealed
-    let command = Command::RevealCurrentItem { id: session_id };
+    let command = Command::RevealCurrentItem {
+        id: login.session_id,
+        member: login.member,
+    };
     route_state.sender.send(command).await?;
     clue_view.revealed();
     Ok(clues::construct_clues_form(
-        session_id, team_name, clue_view,
+        login, team_name, clue_view, locked_out, None,
     ))
 }
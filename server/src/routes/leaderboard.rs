@@ -1,53 +1,66 @@
 use {
-    crate::{RouteState, state::command::Command},
+    crate::{
+        RouteState,
+        routes::auth::Login,
+        state::{command::Command, hunt::HuntId},
+    },
     axum::{
         extract::{Path, State},
         response::Html,
     },
-    tokio::sync::{mpsc, oneshot},
-    treasure_hunt_core::session::SessionId,
+    minijinja::context,
+    tokio::sync::oneshot,
 };
 
-pub async fn action(
+/// The leaderboard of the logged-in team's hunt, or of the default hunt.
+pub async fn action(State(route_state): State<RouteState>, login: Option<Login>) -> Html<String> {
+    inner_leaderboard(route_state, None, login)
+        .await
+        .unwrap_or_else(super::error_to_html)
+}
+
+pub async fn hunt_action(
     State(route_state): State<RouteState>,
-    Path(session_id): Path<String>,
+    Path(hunt_id): Path<String>,
+    login: Option<Login>,
 ) -> Html<String> {
-    async fn inner_leaderboard(
-        sender: mpsc::Sender<Command>,
-        session_id: String,
-    ) -> anyhow::Result<Html<String>> {
-        let (tx, rx) = oneshot::channel();
-        let command = Command::Leaderboard {
-            maybe_id: session_id.clone(),
-            response: tx,
-        };
-        sender.send(command).await?;
-        let (rows, team_name) = rx.await?;
-        let mut result = String::new();
-        result.push_str("<table>\n<tr><th>Team Name</th><th>Score</th></tr>\n");
-        for row in rows {
-            if Some(&row.team_name) == team_name.as_ref() {
-                result.push_str(&format!(
-                    "<tr><td><b>{}</b></td><td><b>{}</b></td></tr>\n",
-                    row.team_name, row.score
-                ));
-            } else {
-                result.push_str(&format!(
-                    "<tr><td>{}</td><td>{}</td></tr>\n",
-                    row.team_name, row.score
-                ));
-            }
-        }
-        result.push_str("</table>");
-        let team_data = team_name.and_then(|name| {
-            Some(super::TeamData {
-                team_name: name,
-                session_id: SessionId::new(&session_id)?,
-            })
-        });
-        Ok(super::fill_body(&result, team_data))
-    }
-    inner_leaderboard(route_state.sender, session_id)
+    inner_leaderboard(route_state, Some(&hunt_id), login)
         .await
         .unwrap_or_else(super::error_to_html)
 }
+
+async fn inner_leaderboard(
+    route_state: RouteState,
+    hunt_id: Option<&str>,
+    login: Option<Login>,
+) -> anyhow::Result<Html<String>> {
+    let hunt = hunt_id.map(HuntId::new).transpose()?;
+    let (tx, rx) = oneshot::channel();
+    let command = Command::Leaderboard {
+        hunt,
+        maybe_id: login.as_ref().map(|l| l.session_id),
+        live: false,
+        response: tx,
+    };
+    route_state.sender.send(command).await?;
+    let leaderboard = rx.await??;
+    let team_data = leaderboard
+        .team_name
+        .zip(login)
+        .map(|(name, login)| super::TeamData::new(name, &login));
+    let other_hunts: Vec<_> = route_state
+        .config
+        .hunts()
+        .into_iter()
+        .filter(|hunt| hunt.id != leaderboard.hunt.id.as_str())
+        .map(|hunt| context! { id => hunt.id, name => hunt.name })
+        .collect();
+    let frozen_for = leaderboard
+        .frozen_since
+        .map(|since| super::format_duration(since.elapsed().unwrap_or_default()));
+    Ok(super::render(
+        "leaderboard.html",
+        context! { hunt => leaderboard.hunt, rows => leaderboard.rows, other_hunts, frozen_for },
+        team_data,
+    ))
+}
@@ -1,31 +1,106 @@
 use {
-    crate::RouteState,
+    crate::{
+        RouteState,
+        routes::auth::{self, AnonymousCsrf, Login},
+        state::{command::Command, member::MemberName},
+    },
     axum::{
         extract::{Form, Path, State},
-        response::Html,
+        http::header,
+        response::{IntoResponse, Redirect, Response},
     },
-    treasure_hunt_core::session::SessionId,
+    minijinja::context,
+    tokio::sync::oneshot,
 };
 
 pub async fn action(
     State(route_state): State<RouteState>,
+    csrf: AnonymousCsrf,
     Form(input): Form<LoginInput>,
-) -> Html<String> {
+) -> Response {
     async fn inner_login(
         route_state: RouteState,
+        csrf: AnonymousCsrf,
         input: LoginInput,
-    ) -> anyhow::Result<Html<String>> {
-        // Validate session id
-        SessionId::new(&input.session_id)
-            .ok_or_else(|| anyhow::Error::msg("Invalid session ID"))?;
-        Ok(crate::routes::clues::form(State(route_state), Path(input.session_id)).await)
+    ) -> anyhow::Result<Response> {
+        csrf.check(&input.csrf_token)?;
+        let member_name = MemberName::new(&input.member_name)?;
+        let (tx, rx) = oneshot::channel();
+        let command = Command::Login {
+            typed_id: input.session_id,
+            pin: input.pin,
+            member_name,
+            response: tx,
+        };
+        route_state.sender.send(command).await?;
+        let joined = rx.await??;
+
+        let login = Login::new(&route_state.cookie_key, joined.session_id, joined.member);
+        let cookie = login.cookie(&route_state.cookie_key, joined.pin_generation);
+        Ok(([(header::SET_COOKIE, cookie)], Redirect::to("/clue")).into_response())
+    }
+    inner_login(route_state, csrf, input)
+        .await
+        .unwrap_or_else(|e| super::error_to_html(e).into_response())
+}
+
+pub async fn join(
+    State(route_state): State<RouteState>,
+    csrf: AnonymousCsrf,
+    Form(input): Form<JoinInput>,
+) -> Response {
+    async fn inner_join(
+        route_state: RouteState,
+        csrf: AnonymousCsrf,
+        input: JoinInput,
+    ) -> anyhow::Result<Response> {
+        csrf.check(&input.csrf_token)?;
+        let member_name = MemberName::new(&input.member_name)?;
+        let (tx, rx) = oneshot::channel();
+        let command = Command::JoinTeam {
+            invite_code: input.invite_code,
+            member_name,
+            response: tx,
+        };
+        route_state.sender.send(command).await?;
+        let joined = rx.await??;
+
+        let login = Login::new(&route_state.cookie_key, joined.session_id, joined.member);
+        let cookie = login.cookie(&route_state.cookie_key, joined.pin_generation);
+        Ok(([(header::SET_COOKIE, cookie)], Redirect::to("/clue")).into_response())
     }
-    inner_login(route_state, input)
+    inner_join(route_state, csrf, input)
         .await
-        .unwrap_or_else(super::error_to_html)
+        .unwrap_or_else(|e| super::error_to_html(e).into_response())
+}
+
+/// The form opened by scanning a team's join QR code, which only asks for a name.
+pub async fn join_form(Path(invite_code): Path<String>, csrf: AnonymousCsrf) -> Response {
+    let context = context! { csrf_token => csrf.token, invite_code };
+    let html = super::render("join_form.html", context, None);
+    super::register::with_csrf_cookie(&csrf, html)
+}
+
+pub async fn logout() -> Response {
+    (
+        [(header::SET_COOKIE, auth::logout_cookie())],
+        Redirect::to("/"),
+    )
+        .into_response()
 }
 
 #[derive(serde::Deserialize, Debug)]
 pub struct LoginInput {
     session_id: String,
+    member_name: String,
+    #[serde(default)]
+    pin: String,
+    csrf_token: String,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct JoinInput {
+    invite_code: String,
+    member_name: String,
+    csrf_token: String,
 }
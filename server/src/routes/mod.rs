@@ -1,73 +1,228 @@
 use {
-    crate::state::TeamName, axum::response::Html, std::time::Duration,
+    self::auth::Login,
+    crate::state::{
+        TeamName,
+        announcement::{Announcement, Announcements},
+    },
+    axum::response::Html,
+    minijinja::{Environment, Value, context},
+    serde::Serialize,
+    std::{
+        sync::OnceLock,
+        time::{Duration, SystemTime},
+    },
     treasure_hunt_core::session::SessionId,
 };
 
 pub mod about;
+pub mod admin;
 pub mod answer;
+pub mod auth;
+pub mod card;
 pub mod clues;
+pub mod events;
+pub mod help;
 pub mod hint;
 pub mod leaderboard;
 pub mod login;
+pub mod photo;
+pub mod progress;
 pub mod register;
+pub mod scan;
 pub mod skip;
+pub mod spectator;
+pub mod team_codes;
 
-const BODY_PLACEHOLDER: &str = "${{BODY}}";
-const SESSION_ID_PLACEHOLDER: &str = "${{SESSION_ID}}";
-const LOGIN_PLACEHOLDER: &str = "${{LOGIN_SECTION}}";
+/// All templates, statically included in the binary at compile-time.
+#[cfg(not(feature = "html-reload"))]
+const TEMPLATES: &[(&str, &str)] = &[
+    ("template.html", include_str!("../../html/template.html")),
+    ("about.html", include_str!("../../html/about.html")),
+    (
+        "announcements.html",
+        include_str!("../../html/announcements.html"),
+    ),
+    ("anomalies.html", include_str!("../../html/anomalies.html")),
+    (
+        "answer_form.html",
+        include_str!("../../html/answer_form.html"),
+    ),
+    ("clue.html", include_str!("../../html/clue.html")),
+    ("complete.html", include_str!("../../html/complete.html")),
+    (
+        "correct_answer.html",
+        include_str!("../../html/correct_answer.html"),
+    ),
+    ("error.html", include_str!("../../html/error.html")),
+    (
+        "no_attempts_left.html",
+        include_str!("../../html/no_attempts_left.html"),
+    ),
+    (
+        "photo_form.html",
+        include_str!("../../html/photo_form.html"),
+    ),
+    (
+        "photo_queue.html",
+        include_str!("../../html/photo_queue.html"),
+    ),
+    (
+        "photo_submitted.html",
+        include_str!("../../html/photo_submitted.html"),
+    ),
+    ("print.html", include_str!("../../html/print.html")),
+    (
+        "print_cheat_sheet.html",
+        include_str!("../../html/print_cheat_sheet.html"),
+    ),
+    (
+        "print_labels.html",
+        include_str!("../../html/print_labels.html"),
+    ),
+    (
+        "print_routes.html",
+        include_str!("../../html/print_routes.html"),
+    ),
+    ("progress.html", include_str!("../../html/progress.html")),
+    ("reload.html", include_str!("../../html/reload.html")),
+    ("reset_pin.html", include_str!("../../html/reset_pin.html")),
+    ("help_form.html", include_str!("../../html/help_form.html")),
+    (
+        "help_queue.html",
+        include_str!("../../html/help_queue.html"),
+    ),
+    ("hint_form.html", include_str!("../../html/hint_form.html")),
+    ("join_form.html", include_str!("../../html/join_form.html")),
+    (
+        "leaderboard.html",
+        include_str!("../../html/leaderboard.html"),
+    ),
+    (
+        "leaderboard_admin.html",
+        include_str!("../../html/leaderboard_admin.html"),
+    ),
+    (
+        "leaderboard_reveal.html",
+        include_str!("../../html/leaderboard_reveal.html"),
+    ),
+    (
+        "register_form.html",
+        include_str!("../../html/register_form.html"),
+    ),
+    (
+        "scan_sheet.html",
+        include_str!("../../html/scan_sheet.html"),
+    ),
+    ("skip_form.html", include_str!("../../html/skip_form.html")),
+    ("spectator.html", include_str!("../../html/spectator.html")),
+    (
+        "statistics.html",
+        include_str!("../../html/statistics.html"),
+    ),
+    (
+        "team_codes.html",
+        include_str!("../../html/team_codes.html"),
+    ),
+    ("team_card.html", include_str!("../../html/team_card.html")),
+    ("welcome.html", include_str!("../../html/welcome.html")),
+];
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct TeamData {
     pub team_name: TeamName,
     pub session_id: SessionId,
+    pub csrf_token: String,
 }
 
 impl TeamData {
-    fn into_html(self) -> String {
-        format!(
-            r#"<section style="margin-top: 40px;">
-                <h4>You are logged in as {}</h4>
-                <h4>Your Session ID is {}</h4>
-            </section>"#,
-            self.team_name, self.session_id,
-        )
+    pub fn new(team_name: TeamName, login: &Login) -> Self {
+        Self {
+            team_name,
+            session_id: login.session_id,
+            csrf_token: login.csrf_token.clone(),
+        }
     }
 }
 
 // If the `html-reload` feature is enabled then we read the HTML
-// template every time the page is rendered (for ease of debugging).
-// Otherwise, the template is statically included in the binary at
+// templates every time a page is rendered (for ease of debugging).
+// Otherwise, the templates are statically included in the binary at
 // compile-time (for performance).
 #[cfg(feature = "html-reload")]
-fn fetch_template() -> String {
+fn environment() -> Environment<'static> {
     let cargo_path = std::path::Path::new(std::env!("CARGO_MANIFEST_DIR"));
-    let path = cargo_path.join("html/template.html");
-    std::fs::read_to_string(path).unwrap()
+    let mut env = Environment::new();
+    env.set_loader(minijinja::path_loader(cargo_path.join("html")));
+    env
 }
 
 #[cfg(not(feature = "html-reload"))]
-const fn fetch_template() -> &'static str {
-    include_str!("../../html/template.html")
+fn environment() -> &'static Environment<'static> {
+    static ENVIRONMENT: OnceLock<Environment<'static>> = OnceLock::new();
+    ENVIRONMENT.get_or_init(|| {
+        let mut env = Environment::new();
+        for (name, source) in TEMPLATES {
+            env.add_template(name, source)
+                .expect("Included templates are valid");
+        }
+        env
+    })
+}
+
+/// The announcements shown at the top of every page, set once at startup.
+static ANNOUNCEMENTS: OnceLock<Announcements> = OnceLock::new();
+
+pub fn show_announcements(announcements: Announcements) {
+    ANNOUNCEMENTS.set(announcements).ok();
+}
+
+/// All announcements which have not expired, including those for particular teams.
+fn current_announcements() -> Vec<Announcement> {
+    let now = SystemTime::now();
+    ANNOUNCEMENTS.get().map_or_else(Vec::new, |announcements| {
+        announcements
+            .borrow()
+            .iter()
+            .filter(|announcement| !announcement.is_expired(now))
+            .cloned()
+            .collect()
+    })
+}
+
+/// Render the named template. Values from `context` are HTML-escaped by the template engine.
+/// The team data (if any) is made available to all templates for the login section and forms,
+/// along with the announcements for the team.
+fn render(name: &str, context: Value, team_data: Option<TeamData>) -> Html<String> {
+    let session_id = team_data.as_ref().map(|team_data| team_data.session_id);
+    let announcements: Vec<String> = current_announcements()
+        .into_iter()
+        .filter(|announcement| announcement.is_for(session_id))
+        .map(|announcement| announcement.message)
+        .collect();
+    let context = context! { team => team_data, announcements, ..context };
+    let env = environment();
+    let result = env
+        .get_template(name)
+        .and_then(|template| template.render(context));
+    match result {
+        Ok(html) => Html(html),
+        Err(e) => {
+            tracing::error!("Failed to render template {name}: {e:?}");
+            Html(String::from("An error occurred while rendering this page."))
+        }
+    }
 }
 
-fn fill_body(content: &str, team_data: Option<TeamData>) -> Html<String> {
-    let template = fetch_template();
-    let session_id = team_data.as_ref().map(|t| t.session_id.to_string());
-    let login_data = team_data.map(TeamData::into_html).unwrap_or_default();
-    Html(
-        template
-            .replace(BODY_PLACEHOLDER, content)
-            .replace(LOGIN_PLACEHOLDER, &login_data)
-            .replace(
-                SESSION_ID_PLACEHOLDER,
-                session_id.as_deref().unwrap_or(SESSION_ID_PLACEHOLDER),
-            ),
-    )
+/// Render a page which is not shown to teams, e.g. for printing from the command line.
+pub fn render_standalone(name: &str, context: Value) -> anyhow::Result<String> {
+    let env = environment();
+    let html = env.get_template(name)?.render(context)?;
+    Ok(html)
 }
 
 fn error_to_html(e: anyhow::Error) -> Html<String> {
-    let message = format!("An error occurred: {e:?}");
-    fill_body(&message, None)
+    let message = format!("{e:?}");
+    render("error.html", context! { message }, None)
 }
 
 fn format_duration(duration: Duration) -> String {
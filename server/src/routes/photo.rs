@@ -0,0 +1,222 @@
+use {
+    crate::{
+        RouteState,
+        routes::{
+            auth::Login,
+            clues::{self, construct_clues_form},
+        },
+        state::command::Command,
+    },
+    axum::{
+        extract::{Multipart, Path, State},
+        response::Html,
+    },
+    rand::Rng,
+    std::time::{SystemTime, UNIX_EPOCH},
+    tokio::sync::oneshot,
+    treasure_hunt_core::clues::kind::ClueKind,
+};
+
+/// Upload a photo answering the current clue. The photo is saved in the configured
+/// directory and the clue waits for the game master to approve or reject it.
+pub async fn action(
+    State(route_state): State<RouteState>,
+    login: Login,
+    Path(clue_id): Path<String>,
+    multipart: Multipart,
+) -> Html<String> {
+    async fn inner_action(
+        route_state: RouteState,
+        login: Login,
+        clue_id: &str,
+        multipart: Multipart,
+    ) -> anyhow::Result<Html<String>> {
+        let input = PhotoInput::read(multipart).await?;
+        clues::use_current_clue(
+            route_state,
+            login,
+            &input.csrf_token,
+            clue_id,
+            |login, team_name, clue_view, locked_out, route_state| async move {
+                if clue_view.clue.kind != ClueKind::Photo {
+                    let notice = String::from("This clue is not answered with a photo.");
+                    return Ok(construct_clues_form(
+                        login,
+                        team_name,
+                        clue_view,
+                        locked_out,
+                        Some(notice),
+                    ));
+                }
+                let Some(extension) = image_extension(&input.photo) else {
+                    let notice = String::from("That file is not a photo! Try again.");
+                    return Ok(construct_clues_form(
+                        login,
+                        team_name,
+                        clue_view,
+                        locked_out,
+                        Some(notice),
+                    ));
+                };
+                let file = photo_file_name(&login, clue_view.number, extension);
+                let dir = std::path::Path::new(&route_state.config.photos.dir);
+                tokio::fs::create_dir_all(dir).await?;
+                let path = dir.join(&file);
+                tokio::fs::write(&path, &input.photo).await?;
+
+                let (tx, rx) = oneshot::channel();
+                let command = Command::SubmitPhoto {
+                    id: login.session_id,
+                    member: login.member,
+                    file,
+                    response: tx,
+                };
+                let submitted = async {
+                    route_state.sender.send(command).await?;
+                    anyhow::Ok(rx.await??)
+                }
+                .await;
+                if let Err(e) = submitted {
+                    // The game master never sees a photo which was not submitted.
+                    tokio::fs::remove_file(&path).await.ok();
+                    return Err(e);
+                }
+                Ok(super::render(
+                    "photo_submitted.html",
+                    minijinja::context! {},
+                    Some(super::TeamData::new(team_name, &login)),
+                ))
+            },
+        )
+        .await
+    }
+
+    inner_action(route_state, login, &clue_id, multipart)
+        .await
+        .unwrap_or_else(super::error_to_html)
+}
+
+/// A new file name for a photo of the clue numbered `number`. The random suffix keeps
+/// photos uploaded by teammates in the same second apart.
+fn photo_file_name(login: &Login, number: usize, extension: &str) -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let suffix: [u8; 4] = rand::rng().random();
+    format!(
+        "{}-{number}-{seconds}-{}.{extension}",
+        login.session_id,
+        hex::encode(suffix)
+    )
+}
+
+/// The fields of the photo upload form.
+struct PhotoInput {
+    csrf_token: String,
+    photo: Vec<u8>,
+}
+
+impl PhotoInput {
+    async fn read(mut multipart: Multipart) -> anyhow::Result<Self> {
+        let mut csrf_token = None;
+        let mut photo = None;
+        while let Some(field) = multipart.next_field().await? {
+            match field.name() {
+                Some("csrf_token") => csrf_token = Some(field.text().await?),
+                Some("photo") => photo = Some(field.bytes().await?.to_vec()),
+                _ => (),
+            }
+        }
+        let csrf_token = csrf_token.ok_or_else(|| anyhow::anyhow!("Missing CSRF token"))?;
+        match photo {
+            Some(photo) if !photo.is_empty() => Ok(Self { csrf_token, photo }),
+            _ => anyhow::bail!("Choose a photo to upload"),
+        }
+    }
+}
+
+/// The file extension for the image format of `bytes`, recognised from its first few
+/// bytes, or `None` if it is not an image format which phones commonly produce.
+fn image_extension(bytes: &[u8]) -> Option<&'static str> {
+    match bytes {
+        [0xFF, 0xD8, 0xFF, ..] => Some("jpg"),
+        [0x89, b'P', b'N', b'G', ..] => Some("png"),
+        [b'G', b'I', b'F', b'8', ..] => Some("gif"),
+        [
+            b'R',
+            b'I',
+            b'F',
+            b'F',
+            _,
+            _,
+            _,
+            _,
+            b'W',
+            b'E',
+            b'B',
+            b'P',
+            ..,
+        ] => Some("webp"),
+        [
+            _,
+            _,
+            _,
+            _,
+            b'f',
+            b't',
+            b'y',
+            b'p',
+            b'h',
+            b'e',
+            b'i',
+            b'c' | b'x',
+            ..,
+        ] => Some("heic"),
+        _ => None,
+    }
+}
+
+/// The content type to serve a photo saved with the given extension.
+pub fn content_type(extension: &str) -> Option<&'static str> {
+    match extension {
+        "jpg" => Some("image/jpeg"),
+        "png" => Some("image/png"),
+        "gif" => Some("image/gif"),
+        "webp" => Some("image/webp"),
+        "heic" => Some("image/heic"),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_image_extension() {
+    assert_eq!(image_extension(&[0xFF, 0xD8, 0xFF, 0xE0, 0]), Some("jpg"));
+    assert_eq!(image_extension(b"\x89PNG\r\n\x1a\n"), Some("png"));
+    assert_eq!(image_extension(b"RIFF\0\0\0\0WEBPVP8 "), Some("webp"));
+    assert_eq!(image_extension(b"\0\0\0\x18ftypheic"), Some("heic"));
+    assert_eq!(image_extension(b"<script>"), None);
+    assert_eq!(image_extension(&[]), None);
+    for extension in ["jpg", "png", "gif", "webp", "heic"] {
+        assert!(content_type(extension).is_some(), "{extension}");
+    }
+}
+
+#[test]
+fn test_photo_file_name() {
+    let login = Login {
+        session_id: treasure_hunt_core::session::SessionId::new("ABCD").unwrap(),
+        member: 0,
+        csrf_token: String::from("token"),
+    };
+    let file = photo_file_name(&login, 3, "jpg");
+    assert!(
+        file.starts_with("ABCD-3-") && file.ends_with(".jpg"),
+        "{file}"
+    );
+    assert_ne!(
+        file,
+        photo_file_name(&login, 3, "jpg"),
+        "Photos uploaded in the same second have different names"
+    );
+}
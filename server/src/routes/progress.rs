@@ -0,0 +1,75 @@
+use {
+    crate::{
+        RouteState,
+        routes::{TeamData, auth::Login},
+        state::command::Command,
+    },
+    axum::{extract::State, response::Html},
+    minijinja::context,
+    tokio::sync::oneshot,
+    treasure_hunt_core::{
+        clues::status::{KnowledgeKind, Status},
+        session::OTHER_CLUE_PENALTY,
+    },
+};
+
+/// Show the team how its score is made up.
+pub async fn action(State(route_state): State<RouteState>, login: Login) -> Html<String> {
+    async fn inner_progress(route_state: RouteState, login: Login) -> anyhow::Result<Html<String>> {
+        let (tx, rx) = oneshot::channel();
+        let command = Command::ScoreBreakdown {
+            id: login.session_id,
+            response: tx,
+        };
+        route_state.sender.send(command).await?;
+        let (team_name, breakdown) = rx.await??;
+
+        let rows: Vec<_> = breakdown
+            .clues
+            .iter()
+            .map(|clue| {
+                let status = match clue.status {
+                    Status::Unread => "Not started",
+                    Status::Seen { .. } => "In progress",
+                    Status::Skipped { .. } => "Skipped",
+                    Status::Solved { .. } => "Solved",
+                    Status::Pending { .. } => "Awaiting approval",
+                    Status::Declined => "Skipped forever",
+                };
+                let help = clue.status.knowledge().map(|kind| match kind {
+                    KnowledgeKind::Unaided => "None",
+                    KnowledgeKind::WithHint => "Hint",
+                    KnowledgeKind::KnowingItem => "Item revealed",
+                });
+                let solve_time = match clue.status {
+                    Status::Solved { duration, .. } => Some(super::format_duration(duration)),
+                    _ => None,
+                };
+                let penalty = -OTHER_CLUE_PENALTY * clue.other_clue_answers as i32;
+                context! {
+                    number => clue.number,
+                    status,
+                    help,
+                    solve_time,
+                    base => clue.points.base,
+                    time_bonus => clue.points.time_bonus,
+                    penalty,
+                    total => clue.points.total() + penalty,
+                }
+            })
+            .collect();
+        let context = context! {
+            rows,
+            penalty => OTHER_CLUE_PENALTY,
+            penalty_points => breakdown.penalty_points,
+            credit_points => breakdown.credit_points,
+            help_points => breakdown.help_points,
+            total => breakdown.total(),
+        };
+        let team_data = TeamData::new(team_name, &login);
+        Ok(super::render("progress.html", context, Some(team_data)))
+    }
+    inner_progress(route_state, login)
+        .await
+        .unwrap_or_else(super::error_to_html)
+}
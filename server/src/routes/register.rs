@@ -1,52 +1,123 @@
 use {
     crate::{
         RouteState,
-        state::{TeamName, command::Command},
+        routes::{
+            auth::{AnonymousCsrf, Login},
+            card,
+        },
+        state::{TeamName, command::Command, hunt::HuntId, member::MemberName, pin::PinHash},
     },
     axum::{
-        extract::{Form, State},
-        response::Html,
+        extract::{Form, Path, State},
+        http::{HeaderMap, header},
+        response::{Html, IntoResponse, Response},
     },
-    tokio::sync::{mpsc, oneshot},
+    minijinja::context,
+    tokio::sync::oneshot,
 };
 
-pub async fn form() -> Html<String> {
-    super::fill_body(include_str!("../../html/register_form.html"), None)
+pub async fn form(State(route_state): State<RouteState>, csrf: AnonymousCsrf) -> Response {
+    let html = form_html(&route_state, None, &csrf);
+    with_csrf_cookie(&csrf, html)
+}
+
+/// The registration form for one particular hunt.
+pub async fn hunt_form(
+    State(route_state): State<RouteState>,
+    Path(hunt_id): Path<String>,
+    csrf: AnonymousCsrf,
+) -> Response {
+    let html = form_html(&route_state, Some(&hunt_id), &csrf);
+    with_csrf_cookie(&csrf, html)
+}
+
+pub fn with_csrf_cookie(csrf: &AnonymousCsrf, html: Html<String>) -> Response {
+    match csrf.cookie() {
+        Some(cookie) => ([(header::SET_COOKIE, cookie)], html).into_response(),
+        None => html.into_response(),
+    }
+}
+
+/// Teams can pick any hunt, unless the form is for a particular hunt.
+fn form_html(
+    route_state: &RouteState,
+    hunt_id: Option<&str>,
+    csrf: &AnonymousCsrf,
+) -> Html<String> {
+    let hunts: Vec<_> = route_state
+        .config
+        .hunts()
+        .into_iter()
+        .filter(|hunt| hunt_id.is_none_or(|id| id == hunt.id))
+        .map(|hunt| context! { id => hunt.id, name => hunt.name })
+        .collect();
+    if hunts.is_empty() {
+        return super::error_to_html(anyhow::anyhow!("Unknown hunt"));
+    }
+    super::render(
+        "register_form.html",
+        context! { csrf_token => csrf.token, hunts },
+        None,
+    )
 }
 
 pub async fn action(
     State(route_state): State<RouteState>,
+    headers: HeaderMap,
+    csrf: AnonymousCsrf,
     Form(input): Form<RegisterInput>,
-) -> Html<String> {
+) -> Response {
     async fn inner_register(
-        sender: mpsc::Sender<Command>,
+        route_state: RouteState,
+        headers: HeaderMap,
+        csrf: AnonymousCsrf,
         input: RegisterInput,
-    ) -> anyhow::Result<Html<String>> {
-        let raw_team_name = &input.team_name;
-        let team_name = TeamName::new(raw_team_name)?;
+    ) -> anyhow::Result<Response> {
+        csrf.check(&input.csrf_token)?;
+        let team_name = TeamName::new(&input.team_name)?;
+        let member_name = MemberName::new(&input.member_name)?;
+        let hunt = match input.hunt.as_str() {
+            "" => None,
+            hunt => Some(HuntId::new(hunt)?),
+        };
+        let pin = match input.pin.trim() {
+            "" => None,
+            pin => Some(PinHash::new(pin)?),
+        };
         let (tx, rx) = oneshot::channel();
         let command = Command::NewSession {
-            team_name: team_name.clone(),
+            team_name,
+            hunt,
+            member_name,
+            pin,
             response: tx,
         };
-        sender.send(command).await?;
-        let id = rx.await??;
-        let html_body = format!(
-            r#"<h1>Welcome</h1><p>Welcome {raw_team_name}! Your session id is {id}.</p><br><br><p><a href="/clue/{id}">Click here</a> to see the first clue.</p>"#
+        route_state.sender.send(command).await?;
+        let joined = rx.await??;
+        let login = Login::new(&route_state.cookie_key, joined.session_id, joined.member);
+        let team_codes_enabled = route_state.config.team_codes_secret.is_some();
+        let (_, join_qr) = card::join_qr(&route_state.config, &headers, &joined.invite_code)?;
+        let html = super::render(
+            "welcome.html",
+            context! { team_codes_enabled, invite_code => joined.invite_code, join_qr },
+            Some(super::TeamData::new(joined.team_name, &login)),
         );
-        let team_data = super::TeamData {
-            team_name,
-            session_id: id,
-        };
-        Ok(super::fill_body(&html_body, Some(team_data)))
+        let cookie = login.cookie(&route_state.cookie_key, joined.pin_generation);
+        Ok(([(header::SET_COOKIE, cookie)], html).into_response())
     }
 
-    inner_register(route_state.sender, input)
+    inner_register(route_state, headers, csrf, input)
         .await
-        .unwrap_or_else(super::error_to_html)
+        .unwrap_or_else(|e| super::error_to_html(e).into_response())
 }
 
 #[derive(serde::Deserialize, Debug)]
 pub struct RegisterInput {
     team_name: String,
+    #[serde(default)]
+    hunt: String,
+    member_name: String,
+    #[serde(default)]
+    pin: String,
+    csrf_token: String,
 }
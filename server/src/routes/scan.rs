@@ -0,0 +1,89 @@
+use {
+    crate::{
+        RouteState,
+        routes::auth::Login,
+        state::command::{
+            Command,
+            answer::{AnswerOutcome, Guess},
+        },
+    },
+    axum::{
+        extract::{Path, State},
+        response::{IntoResponse, Redirect, Response},
+    },
+    serde::{Deserialize, Serialize},
+    tokio::sync::oneshot,
+    treasure_hunt_core::session::OTHER_CLUE_PENALTY,
+};
+
+/// What happened when a team scanned a QR code, passed on to the clue page so that
+/// reloading it does not scan the code again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Scanned {
+    Correct,
+    LockedOut,
+    NotYourClue,
+    OtherClue,
+    AlreadySolved,
+}
+
+impl Scanned {
+    /// The notice to show on the clue page.
+    pub fn notice(self) -> String {
+        match self {
+            Self::Correct => String::from("Great job! You got the right answer!"),
+            Self::LockedOut => String::from("Too many guesses! That QR code was not checked."),
+            Self::NotYourClue => String::from("That QR code is not for your clue."),
+            Self::OtherClue => format!(
+                "That QR code is for another clue. You lose {OTHER_CLUE_PENALTY} points for your error."
+            ),
+            Self::AlreadySolved => {
+                String::from("You have already solved the clue for that QR code.")
+            }
+        }
+    }
+}
+
+/// Answer the current clue with the token from the QR code on its item, then show the
+/// clue page. Teams open this by scanning the code with a phone which is logged in.
+pub async fn action(
+    State(route_state): State<RouteState>,
+    login: Login,
+    Path(token): Path<String>,
+) -> Response {
+    async fn inner_action(
+        route_state: RouteState,
+        login: Login,
+        token: String,
+    ) -> anyhow::Result<Response> {
+        if route_state.config.scan_secret.is_none() {
+            anyhow::bail!("QR codes are not used in this hunt");
+        }
+        let (tx, rx) = oneshot::channel();
+        let command = Command::AnswerCurrentClue {
+            id: login.session_id,
+            member: login.member,
+            guess: Guess::Scanned(token),
+            response: tx,
+        };
+        route_state.sender.send(command).await?;
+        let scanned = match rx.await?? {
+            AnswerOutcome::LockedOut(_) => Scanned::LockedOut,
+            AnswerOutcome::AlreadySolved => Scanned::AlreadySolved,
+            // The clue page shows any lockout this scan caused.
+            AnswerOutcome::Checked { points, .. } => match points {
+                None => Scanned::NotYourClue,
+                Some(points) if points >= 0 => Scanned::Correct,
+                Some(_) => Scanned::OtherClue,
+            },
+        };
+        let query = serde_json::to_value(scanned)?;
+        let query = query.as_str().expect("Scanned is serialized as a string");
+        Ok(Redirect::to(&format!("/clue?scanned={query}")).into_response())
+    }
+
+    inner_action(route_state, login, token)
+        .await
+        .unwrap_or_else(|e| super::error_to_html(e).into_response())
+}
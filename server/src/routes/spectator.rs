@@ -0,0 +1,88 @@
+//! A read-only page for a big screen, cycling through the leaderboard, recent solves,
+//! where the teams are and the time remaining. Solves are pushed to it as they happen.
+
+use {
+    crate::{
+        RouteState,
+        state::{command::Command, hunt::HuntId},
+    },
+    axum::{
+        extract::{Path, State},
+        response::{
+            Html,
+            sse::{Event, KeepAlive, Sse},
+        },
+    },
+    minijinja::context,
+    std::{convert::Infallible, time::UNIX_EPOCH},
+    tokio::sync::oneshot,
+    tokio_stream::{Stream, StreamExt, wrappers::BroadcastStream},
+};
+
+/// The spectator page for the default hunt.
+pub async fn action(State(route_state): State<RouteState>) -> Html<String> {
+    inner_spectator(route_state, None)
+        .await
+        .unwrap_or_else(super::error_to_html)
+}
+
+pub async fn hunt_action(
+    State(route_state): State<RouteState>,
+    Path(hunt_id): Path<String>,
+) -> Html<String> {
+    inner_spectator(route_state, Some(&hunt_id))
+        .await
+        .unwrap_or_else(super::error_to_html)
+}
+
+/// A stream of the solves in the hunt, for open spectator pages.
+pub async fn events(
+    State(route_state): State<RouteState>,
+    Path(hunt_id): Path<String>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let events = BroadcastStream::new(route_state.spectator_events.subscribe());
+    let stream = events.filter_map(move |event| {
+        // Lagging behind only means missing some events; the page reloads anyway.
+        let event = event.ok()?;
+        (event.hunt.as_str() == hunt_id).then(|| Ok(Event::default().data(event.message)))
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+async fn inner_spectator(
+    route_state: RouteState,
+    hunt_id: Option<&str>,
+) -> anyhow::Result<Html<String>> {
+    let hunt = hunt_id.map(HuntId::new).transpose()?;
+    let (tx, rx) = oneshot::channel();
+    let command = Command::Spectator { hunt, response: tx };
+    route_state.sender.send(command).await?;
+    let view = rx.await??;
+    let recent_solves: Vec<_> = view
+        .recent_solves
+        .iter()
+        .map(|solve| {
+            let ago = super::format_duration(solve.time.elapsed().unwrap_or_default());
+            context! { message => solve.message, ago }
+        })
+        .collect();
+    // The page counts down to the end of the hunt itself.
+    let end_time_ms = route_state
+        .config
+        .hunt_settings(view.leaderboard.hunt.id.as_str())
+        .end_time
+        .and_then(|end_time| end_time.inner.duration_since(UNIX_EPOCH).ok())
+        .map(|since_epoch| since_epoch.as_millis() as u64);
+    Ok(super::render(
+        "spectator.html",
+        context! {
+            hunt => view.leaderboard.hunt,
+            rows => view.leaderboard.rows,
+            is_frozen => view.leaderboard.frozen_since.is_some(),
+            recent_solves,
+            occupancy => view.occupancy,
+            end_time_ms,
+        },
+        None,
+    ))
+}
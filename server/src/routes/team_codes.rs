@@ -0,0 +1,30 @@
+use {
+    crate::{RouteState, routes::auth::Login, state::command::Command},
+    axum::{extract::State, response::Html},
+    minijinja::context,
+    tokio::sync::{mpsc, oneshot},
+};
+
+/// A printable sheet of the team's answer codes, used when team-specific answers are enabled.
+pub async fn action(State(route_state): State<RouteState>, login: Login) -> Html<String> {
+    async fn inner_team_codes(
+        sender: mpsc::Sender<Command>,
+        login: Login,
+    ) -> anyhow::Result<Html<String>> {
+        let (tx, rx) = oneshot::channel();
+        let command = Command::TeamCodes {
+            id: login.session_id,
+            response: tx,
+        };
+        sender.send(command).await?;
+        let (team_name, codes) = rx.await??;
+        Ok(super::render(
+            "team_codes.html",
+            context! { codes },
+            Some(super::TeamData::new(team_name, &login)),
+        ))
+    }
+    inner_team_codes(route_state.sender, login)
+        .await
+        .unwrap_or_else(super::error_to_html)
+}
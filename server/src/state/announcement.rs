@@ -0,0 +1,54 @@
+//! Messages from the organisers shown at the top of every page, e.g. "Pizza is ready!".
+
+use {std::time::SystemTime, tokio::sync::watch, treasure_hunt_core::session::SessionId};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Announcement {
+    pub id: u64,
+    pub message: String,
+    pub created: SystemTime,
+    /// When to stop showing the announcement, if ever.
+    pub expires: Option<SystemTime>,
+    /// The teams to show the announcement to, or everyone (even if not logged in) if empty.
+    pub teams: Vec<SessionId>,
+}
+
+impl Announcement {
+    pub fn is_expired(&self, now: SystemTime) -> bool {
+        self.expires.is_some_and(|expires| expires <= now)
+    }
+
+    /// Whether to show the announcement to the team with the given session ID, or to
+    /// someone who is not logged in.
+    pub fn is_for(&self, session_id: Option<SessionId>) -> bool {
+        self.teams.is_empty() || session_id.is_some_and(|id| self.teams.contains(&id))
+    }
+}
+
+/// The current announcements, which routes read when rendering pages.
+pub type Announcements = watch::Receiver<Vec<Announcement>>;
+
+#[test]
+fn test_announcement() {
+    use std::time::Duration;
+
+    let now = SystemTime::now();
+    let team = SessionId::new("ABCD").unwrap();
+    let other = SessionId::new("EFGH").unwrap();
+    let mut announcement = Announcement {
+        id: 1,
+        message: String::from("Pizza is ready!"),
+        created: now,
+        expires: None,
+        teams: Vec::new(),
+    };
+    assert!(!announcement.is_expired(now));
+    assert!(announcement.is_for(None) && announcement.is_for(Some(team)));
+
+    announcement.expires = Some(now + Duration::from_secs(60));
+    announcement.teams = vec![team];
+    assert!(!announcement.is_expired(now));
+    assert!(announcement.is_expired(now + Duration::from_secs(60)));
+    assert!(announcement.is_for(Some(team)));
+    assert!(!announcement.is_for(Some(other)) && !announcement.is_for(None));
+}
@@ -0,0 +1,72 @@
+use {
+    crate::state::{State, announcement::Announcement},
+    std::{fmt, time::SystemTime},
+    tokio::sync::oneshot,
+    treasure_hunt_core::session::SessionId,
+};
+
+#[derive(Debug)]
+pub enum AnnounceError {
+    UnknownSessionId(SessionId),
+}
+
+impl fmt::Display for AnnounceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownSessionId(id) => write!(f, "Unknown session ID {id}"),
+        }
+    }
+}
+
+impl std::error::Error for AnnounceError {}
+
+pub async fn handle(
+    state: &mut State,
+    message: String,
+    expires: Option<SystemTime>,
+    teams: Vec<SessionId>,
+    response: oneshot::Sender<Result<(), AnnounceError>>,
+) {
+    if let Some(id) = teams.iter().find(|id| !state.sessions.contains_key(id)) {
+        response
+            .send(Err(AnnounceError::UnknownSessionId(*id)))
+            .ok();
+        return;
+    }
+    let now = SystemTime::now();
+    state.announcements.send_modify(|announcements| {
+        announcements.retain(|announcement| !announcement.is_expired(now));
+        let id = announcements.iter().map(|a| a.id).max().unwrap_or(0) + 1;
+        announcements.push(Announcement {
+            id,
+            message: message.clone(),
+            created: now,
+            expires,
+            teams: teams.clone(),
+        });
+    });
+    if teams.is_empty() {
+        tracing::info!("Announced to everyone: {message}");
+    } else {
+        tracing::info!("Announced to {} team(s): {message}", teams.len());
+    }
+    response.send(Ok(())).ok();
+    let notification = format!("Announcement: {message}");
+    if teams.is_empty() {
+        state.notify_everyone(notification);
+    } else {
+        for id in teams {
+            state.notify_whole_team(id, notification.clone());
+        }
+    }
+    state.writer.send(state.serialize()).await.ok();
+}
+
+pub async fn handle_remove(state: &mut State, id: u64, response: oneshot::Sender<()>) {
+    let now = SystemTime::now();
+    state.announcements.send_modify(|announcements| {
+        announcements.retain(|announcement| announcement.id != id && !announcement.is_expired(now));
+    });
+    response.send(()).ok();
+    state.writer.send(state.serialize()).await.ok();
+}
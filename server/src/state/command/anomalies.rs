@@ -0,0 +1,21 @@
+use {
+    crate::state::{State, TeamName},
+    std::collections::HashMap,
+    tokio::sync::oneshot,
+    treasure_hunt_core::{
+        anomaly::{self, Anomaly},
+        session::SessionId,
+    },
+};
+
+pub type AnomalyReport = (Vec<Anomaly>, HashMap<SessionId, TeamName>);
+
+pub fn handle(state: &State, response: oneshot::Sender<AnomalyReport>) {
+    let anomalies = anomaly::detect(state.sessions.values().map(|t| &t.session));
+    let team_names = state
+        .sessions
+        .iter()
+        .map(|(id, t)| (*id, t.name.clone()))
+        .collect();
+    response.send((anomalies, team_names)).ok();
+}
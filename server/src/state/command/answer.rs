@@ -1,23 +1,107 @@
 use {
-    crate::state::{State, command::CurrentClueError},
+    crate::state::{
+        State,
+        command::CurrentClueError,
+        member::{MemberId, MemberName},
+    },
+    std::time::{Duration, Instant},
     tokio::sync::oneshot,
     treasure_hunt_core::session::SessionId,
 };
 
+/// What a team submitted to answer their current clue.
+#[derive(Debug)]
+pub enum Guess {
+    Typed(String),
+    /// The token from the URL in a clue's QR code.
+    Scanned(String),
+}
+
+#[derive(Debug)]
+pub enum AnswerOutcome {
+    /// The answer was checked; see `Session::try_solve` for the meaning of the points.
+    /// `locked_out` is how long the team must wait before answering again, e.g. because
+    /// this answer was one wrong answer too many.
+    Checked {
+        points: Option<i32>,
+        locked_out: Option<Duration>,
+    },
+    /// The team has made too many guesses and must wait before answering again.
+    LockedOut(Duration),
+    /// The QR code is for a clue the team has already solved, so nothing changed.
+    AlreadySolved,
+}
+
 pub async fn handle(
     state: &mut State,
     id: &SessionId,
-    guess: &str,
-    response: oneshot::Sender<Result<Option<i32>, CurrentClueError>>,
+    member: MemberId,
+    guess: &Guess,
+    response: oneshot::Sender<Result<AnswerOutcome, CurrentClueError>>,
 ) {
-    response.send(inner(state, id, guess)).ok();
+    let outcome = inner(state, id, member, guess);
+    match &outcome {
+        Ok(AnswerOutcome::Checked {
+            points: Some(points),
+            ..
+        }) if *points >= 0 => {
+            state.notify_team(*id, member, "solved the clue!");
+            let solved = state
+                .sessions
+                .get(id)
+                .and_then(|team_session| team_session.session.history().last())
+                .map(|entry| entry.clue_code);
+            if let Some(clue_code) = solved {
+                state.notify_spectators(*id, &clue_code);
+            }
+        }
+        Ok(AnswerOutcome::Checked {
+            points: Some(points),
+            ..
+        }) => {
+            let action = format!("answered another clue ({points} points).");
+            state.notify_team(*id, member, &action);
+        }
+        _ => (),
+    }
+    response.send(outcome).ok();
     state.writer.send(state.serialize()).await.ok();
 }
 
-fn inner(state: &mut State, id: &SessionId, guess: &str) -> Result<Option<i32>, CurrentClueError> {
+fn inner(
+    state: &mut State,
+    id: &SessionId,
+    member: MemberId,
+    guess: &Guess,
+) -> Result<AnswerOutcome, CurrentClueError> {
     let team_session = state
         .sessions
         .get_mut(id)
         .ok_or(CurrentClueError::UnknownSessionId)?;
-    Ok(team_session.session.try_solve(guess))
+
+    // Scanning a solved clue again, e.g. by reloading the page, is not a guess.
+    if let (Guess::Scanned(token), Some(key)) = (guess, &state.scan_key) {
+        if team_session.session.has_solved_scanned(token, key) {
+            return Ok(AnswerOutcome::AlreadySolved);
+        }
+    }
+
+    let now = Instant::now();
+    let limiter = state.guess_limiters.entry(*id).or_default();
+    if let Some(remaining) = limiter.check(&state.guess_limits, now) {
+        return Ok(AnswerOutcome::LockedOut(remaining));
+    }
+
+    let member_name = team_session.members.get(member).map(MemberName::as_str);
+    let session = &mut team_session.session;
+    let points = match (guess, &state.scan_key) {
+        (Guess::Typed(answer), _) => session.try_solve(answer, &state.answer_mode, member_name),
+        (Guess::Scanned(token), Some(key)) => session.try_solve_scanned(token, key, member_name),
+        // Routes only send scans when QR codes are enabled.
+        (Guess::Scanned(_), None) => None,
+    };
+    let is_correct = points.is_some_and(|x| x >= 0);
+    limiter.record(&state.guess_limits, now, is_correct);
+    let locked_out = limiter.check(&state.guess_limits, now);
+    Ok(AnswerOutcome::Checked { points, locked_out })
 }
@@ -0,0 +1,29 @@
+use {
+    crate::state::{State, hunt::HuntId},
+    tokio::sync::oneshot,
+};
+
+/// The orders in which a hunt's clues are given to new teams.
+#[derive(Debug)]
+pub struct HuntArrangements {
+    pub hunt: HuntId,
+    /// The clue IDs of each precomputed arrangement, in order.
+    pub arrangements: Vec<Vec<String>>,
+}
+
+pub fn handle(state: &State, response: oneshot::Sender<Vec<HuntArrangements>>) {
+    let arrangements = state
+        .hunts
+        .iter()
+        .map(|hunt| HuntArrangements {
+            hunt: hunt.id.clone(),
+            arrangements: hunt
+                .arrangements()
+                .precomputed()
+                .iter()
+                .map(|clues| clues.0.iter().map(|clue| clue.id.clone()).collect())
+                .collect(),
+        })
+        .collect();
+    response.send(arrangements).ok();
+}
@@ -3,9 +3,9 @@ use {
         State, TeamName,
         command::{ClueOrScore, Either},
     },
-    std::fmt,
+    std::{fmt, time::Instant},
     tokio::sync::oneshot,
-    treasure_hunt_core::{clues::ClueView, session::SessionId},
+    treasure_hunt_core::session::SessionId,
 };
 
 #[derive(Debug)]
@@ -30,17 +30,18 @@ pub async fn handle(
     state.writer.send(state.serialize()).await.ok();
 }
 
-fn inner(
-    state: &mut State,
-    id: &SessionId,
-) -> Result<(TeamName, Either<ClueView, i32>), CurrentClueError> {
+fn inner(state: &mut State, id: &SessionId) -> Result<(TeamName, ClueOrScore), CurrentClueError> {
     let team_session = state
         .sessions
         .get_mut(id)
         .ok_or(CurrentClueError::UnknownSessionId)?;
+    let locked_out = state
+        .guess_limiters
+        .get_mut(id)
+        .and_then(|limiter| limiter.check(&state.guess_limits, Instant::now()));
     let result = team_session.session.current_clue().map_or_else(
         || Either::Right(team_session.session.total_score()),
-        Either::Left,
+        |clue_view| Either::Left((clue_view, locked_out)),
     );
     Ok((team_session.name.clone(), result))
 }
@@ -0,0 +1,41 @@
+use {
+    crate::state::{
+        State,
+        freeze::FrozenLeaderboard,
+        hunt::{HuntId, UnknownHunt},
+    },
+    std::time::SystemTime,
+    tokio::sync::oneshot,
+};
+
+/// Freeze or unfreeze the leaderboard of a hunt, by default the first. Responds with the
+/// time it has been frozen since, if any.
+pub async fn handle(
+    state: &mut State,
+    hunt: Option<HuntId>,
+    freeze: bool,
+    response: oneshot::Sender<Result<Option<SystemTime>, UnknownHunt>>,
+) {
+    let Some(hunt_id) = state.hunt(hunt.as_ref()).map(|hunt| hunt.id.clone()) else {
+        response.send(Err(UnknownHunt)).ok();
+        return;
+    };
+    match (freeze, state.frozen_leaderboards.contains_key(&hunt_id)) {
+        (true, false) => {
+            tracing::info!("Freezing the leaderboard Hunt={hunt_id}");
+            let frozen = FrozenLeaderboard::new(&state.sessions, &hunt_id);
+            state.frozen_leaderboards.insert(hunt_id.clone(), frozen);
+        }
+        (false, true) => {
+            tracing::info!("Unfreezing the leaderboard Hunt={hunt_id}");
+            state.frozen_leaderboards.remove(&hunt_id);
+        }
+        _ => (),
+    }
+    let since = state
+        .frozen_leaderboards
+        .get(&hunt_id)
+        .map(|frozen| frozen.since);
+    response.send(Ok(since)).ok();
+    state.writer.send(state.serialize()).await.ok();
+}
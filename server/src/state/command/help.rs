@@ -0,0 +1,126 @@
+use {
+    crate::state::{
+        State, TeamName,
+        member::{MemberId, MemberName},
+    },
+    std::{fmt, time::SystemTime},
+    tokio::sync::oneshot,
+    treasure_hunt_core::session::{SessionId, history::HistoryEvent},
+};
+
+#[derive(Debug)]
+pub enum HelpError {
+    UnknownSessionId,
+    /// The team has finished, so there is no clue to help with.
+    NoCurrentClue,
+    /// The reply is about a clue the team does not have.
+    UnknownClue,
+}
+
+impl fmt::Display for HelpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownSessionId => f.write_str("Unknown session ID"),
+            Self::NoCurrentClue => f.write_str("There is no clue to help with"),
+            Self::UnknownClue => f.write_str("The team does not have that clue"),
+        }
+    }
+}
+
+impl std::error::Error for HelpError {}
+
+/// A request for help waiting in the game master's queue.
+#[derive(Debug)]
+pub struct HelpRequest {
+    pub session_id: SessionId,
+    pub team_name: TeamName,
+    pub clue_number: usize,
+    pub clue_id: String,
+    pub clue_code: [u8; 32],
+    pub member: Option<String>,
+    pub message: String,
+    pub time: SystemTime,
+}
+
+pub async fn handle_request(
+    state: &mut State,
+    id: &SessionId,
+    member: MemberId,
+    message: &str,
+    response: oneshot::Sender<Result<(), HelpError>>,
+) {
+    let Some(team_session) = state.sessions.get_mut(id) else {
+        response.send(Err(HelpError::UnknownSessionId)).ok();
+        return;
+    };
+    let member_name = team_session.members.get(member).map(MemberName::as_str);
+    if !team_session.session.request_help(message, member_name) {
+        response.send(Err(HelpError::NoCurrentClue)).ok();
+        return;
+    }
+    tracing::info!("{} asked for help: {message}", team_session.name);
+    response.send(Ok(())).ok();
+    state.notify_team(*id, member, "asked the game master for help.");
+    state.writer.send(state.serialize()).await.ok();
+}
+
+/// All pending requests for help, oldest first.
+pub fn handle_queue(state: &State, response: oneshot::Sender<Vec<HelpRequest>>) {
+    let mut requests: Vec<HelpRequest> = state
+        .sessions
+        .iter()
+        .flat_map(|(id, team_session)| {
+            let session = &team_session.session;
+            session
+                .pending_help_requests()
+                .into_iter()
+                .filter_map(move |entry| {
+                    let HistoryEvent::HelpRequest { message } = &entry.event else {
+                        return None;
+                    };
+                    let (index, (clue, _)) = session
+                        .clues()
+                        .enumerate()
+                        .find(|(_, (clue, _))| clue.code == entry.clue_code)?;
+                    Some(HelpRequest {
+                        session_id: *id,
+                        team_name: team_session.name.clone(),
+                        clue_number: index + 1,
+                        clue_id: clue.id.clone(),
+                        clue_code: clue.code,
+                        member: entry.member.clone(),
+                        message: message.clone(),
+                        time: entry.time,
+                    })
+                })
+        })
+        .collect();
+    requests.sort_by_key(|request| request.time);
+    response.send(requests).ok();
+}
+
+pub async fn handle_reply(
+    state: &mut State,
+    id: &SessionId,
+    clue_code: &[u8; 32],
+    message: &str,
+    points: i32,
+    response: oneshot::Sender<Result<TeamName, HelpError>>,
+) {
+    let Some(team_session) = state.sessions.get_mut(id) else {
+        response.send(Err(HelpError::UnknownSessionId)).ok();
+        return;
+    };
+    if !team_session
+        .session
+        .reply_to_help(clue_code, message, points)
+    {
+        response.send(Err(HelpError::UnknownClue)).ok();
+        return;
+    }
+    let team_name = team_session.name.clone();
+    tracing::info!("Replied to {team_name} charging {points} points: {message}");
+    response.send(Ok(team_name)).ok();
+    state.notify_whole_team(*id, String::from("The game master replied!"));
+    state.writer.send(state.serialize()).await.ok();
+}
@@ -1,25 +1,37 @@
-use {crate::state::State, treasure_hunt_core::session::SessionId};
+use {
+    crate::state::{
+        State,
+        member::{MemberId, MemberName},
+    },
+    treasure_hunt_core::session::SessionId,
+};
 
-pub async fn handle_hint(state: &mut State, id: &SessionId) {
+pub async fn handle_hint(state: &mut State, id: &SessionId, member: MemberId) {
     let Some(team_session) = state.sessions.get_mut(id) else {
         return;
     };
-    team_session.session.hint_current_clue();
+    let member_name = team_session.members.get(member).map(MemberName::as_str);
+    team_session.session.hint_current_clue(member_name);
+    state.notify_team(*id, member, "asked for a hint.");
     state.writer.send(state.serialize()).await.ok();
 }
 
-pub async fn handle_reveal(state: &mut State, id: &SessionId) {
+pub async fn handle_reveal(state: &mut State, id: &SessionId, member: MemberId) {
     let Some(team_session) = state.sessions.get_mut(id) else {
         return;
     };
-    team_session.session.reveal_current_item();
+    let member_name = team_session.members.get(member).map(MemberName::as_str);
+    team_session.session.reveal_current_item(member_name);
+    state.notify_team(*id, member, "revealed the item.");
     state.writer.send(state.serialize()).await.ok();
 }
 
-pub async fn handle_skip(state: &mut State, id: &SessionId) {
+pub async fn handle_skip(state: &mut State, id: &SessionId, member: MemberId) {
     let Some(team_session) = state.sessions.get_mut(id) else {
         return;
     };
-    team_session.session.skip_current_clue();
+    let member_name = team_session.members.get(member).map(MemberName::as_str);
+    team_session.session.skip_current_clue(member_name);
+    state.notify_team(*id, member, "skipped the clue.");
     state.writer.send(state.serialize()).await.ok();
 }
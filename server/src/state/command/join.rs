@@ -0,0 +1,75 @@
+use {
+    crate::state::{
+        State, TeamName,
+        member::{self, MemberId, MemberName},
+    },
+    std::fmt,
+    tokio::sync::oneshot,
+    treasure_hunt_core::session::SessionId,
+};
+
+/// A member who has joined (or rejoined) a team.
+#[derive(Debug)]
+pub struct Joined {
+    pub session_id: SessionId,
+    pub member: MemberId,
+    pub team_name: TeamName,
+    pub invite_code: String,
+    /// To be signed into the session cookie; see `TeamSession::pin_generation`.
+    pub pin_generation: u32,
+}
+
+#[derive(Debug)]
+pub enum JoinError {
+    UnknownInviteCode,
+}
+
+impl fmt::Display for JoinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownInviteCode => f.write_str("Unknown invite code"),
+        }
+    }
+}
+
+impl std::error::Error for JoinError {}
+
+pub async fn handle(
+    state: &mut State,
+    invite_code: &str,
+    member_name: MemberName,
+    response: oneshot::Sender<Result<Joined, JoinError>>,
+) {
+    let invite_code = member::normalize_invite_code(invite_code);
+    let Some((id, _)) = state
+        .sessions
+        .iter()
+        .find(|(_, team_session)| team_session.invite_code == invite_code)
+    else {
+        response.send(Err(JoinError::UnknownInviteCode)).ok();
+        return;
+    };
+    let id = *id;
+    response.send(Ok(join(state, id, member_name))).ok();
+    state.writer.send(state.serialize()).await.ok();
+}
+
+/// Add a member to a team which is known to exist.
+pub fn join(state: &mut State, session_id: SessionId, member_name: MemberName) -> Joined {
+    let team_session = state
+        .sessions
+        .get_mut(&session_id)
+        .expect("Only called for existing sessions");
+    tracing::info!(
+        "Member {member_name} joined TeamName={} SessionId={session_id}",
+        team_session.name
+    );
+    let member = team_session.add_member(member_name);
+    Joined {
+        session_id,
+        member,
+        team_name: team_session.name.clone(),
+        invite_code: team_session.invite_code.clone(),
+        pin_generation: team_session.pin_generation,
+    }
+}
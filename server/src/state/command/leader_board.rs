@@ -1,29 +1,102 @@
 use {
-    crate::state::{State, TeamName},
+    crate::state::{
+        State, TeamName, TeamSession,
+        hunt::{Hunt, HuntId, HuntSummary, UnknownHunt},
+    },
+    serde::Serialize,
+    std::time::SystemTime,
     tokio::sync::oneshot,
+    treasure_hunt_core::session::{
+        SessionId,
+        standing::{self, Standing},
+    },
 };
 
 #[derive(Debug)]
+pub struct Leaderboard {
+    pub hunt: HuntSummary,
+    pub rows: Vec<LeaderboardRow>,
+    /// Name of the logged-in team, if any.
+    pub team_name: Option<TeamName>,
+    /// When the standings shown were frozen, unless they are live.
+    pub frozen_since: Option<SystemTime>,
+}
+
+#[derive(Debug, Serialize)]
 pub struct LeaderboardRow {
+    pub rank: usize,
+    /// True if another team has the same rank.
+    pub is_tied: bool,
     pub team_name: TeamName,
     pub score: i32,
+    pub clues_solved: usize,
+    pub clues_remaining: usize,
+    pub hints_used: usize,
+    pub streak: usize,
 }
 
 pub fn handle(
     state: &State,
-    maybe_id: String,
-    response: oneshot::Sender<(Vec<LeaderboardRow>, Option<TeamName>)>,
+    hunt: Option<HuntId>,
+    maybe_id: Option<SessionId>,
+    live: bool,
+    response: oneshot::Sender<Result<Leaderboard, UnknownHunt>>,
 ) {
-    let team_name = state.get_team_name(&maybe_id).cloned();
-    let mut rows = Vec::new();
-    for team_session in state.sessions.values() {
-        let score = team_session.session.total_score();
-        let row = LeaderboardRow {
-            team_name: team_session.name.clone(),
-            score,
-        };
-        rows.push(row);
+    let team_session = maybe_id.and_then(|id| state.sessions.get(&id));
+    let hunt_id = hunt.or_else(|| team_session.map(|team_session| team_session.hunt.clone()));
+    let Some(hunt) = state.hunt(hunt_id.as_ref()) else {
+        response.send(Err(UnknownHunt)).ok();
+        return;
+    };
+    response
+        .send(Ok(leaderboard(state, hunt, team_session, live)))
+        .ok();
+}
+
+/// The leaderboard of `hunt`, as seen by the team of `team_session` if any.
+pub fn leaderboard(
+    state: &State,
+    hunt: &Hunt,
+    team_session: Option<&TeamSession>,
+    live: bool,
+) -> Leaderboard {
+    let frozen = state.frozen_leaderboards.get(&hunt.id).filter(|_| !live);
+    // Teams which registered after the freeze are shown as they are now.
+    let mut standings: Vec<(&TeamName, Standing)> = state
+        .sessions
+        .iter()
+        .filter(|(_, team_session)| team_session.hunt == hunt.id)
+        .map(|(id, team_session)| {
+            let standing = frozen
+                .and_then(|frozen| frozen.standings.get(id).cloned())
+                .unwrap_or_else(|| team_session.session.standing());
+            (&team_session.name, standing)
+        })
+        .collect();
+    // Teams which are still tied after the tie-breakers are listed by name.
+    standings.sort_by(|(a_name, a), (b_name, b)| {
+        a.ranking_cmp(b)
+            .then_with(|| a_name.to_string().cmp(&b_name.to_string()))
+    });
+    let ranks = standing::ranks(&standings.iter().map(|(_, s)| s).collect::<Vec<_>>());
+    let rows = standings
+        .into_iter()
+        .zip(ranks)
+        .map(|((team_name, standing), rank)| LeaderboardRow {
+            rank: rank.position,
+            is_tied: rank.is_tied,
+            team_name: team_name.clone(),
+            score: standing.score,
+            clues_solved: standing.clues_solved,
+            clues_remaining: standing.clues_remaining,
+            hints_used: standing.hints_used,
+            streak: standing.streak,
+        })
+        .collect();
+    Leaderboard {
+        hunt: hunt.summary(),
+        rows,
+        team_name: team_session.map(|team_session| team_session.name.clone()),
+        frozen_since: frozen.map(|frozen| frozen.since),
     }
-    rows.sort_by_key(|r| -r.score);
-    response.send((rows, team_name)).ok();
 }
@@ -0,0 +1,118 @@
+use {
+    crate::state::{
+        State, TeamName,
+        command::join::{self, Joined},
+        member::MemberName,
+        pin::PinHash,
+    },
+    std::{
+        fmt,
+        time::{Duration, Instant},
+    },
+    tokio::sync::oneshot,
+    treasure_hunt_core::session::{SessionId, SessionIdError},
+};
+
+#[derive(Debug)]
+pub enum LoginError {
+    InvalidSessionId(SessionIdError),
+    UnknownSessionId,
+    WrongPin,
+    LockedOut(Duration),
+}
+
+impl fmt::Display for LoginError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSessionId(e) => write!(f, "{e}"),
+            Self::UnknownSessionId => f.write_str("Unknown session ID"),
+            Self::WrongPin => f.write_str("Wrong PIN"),
+            Self::LockedOut(remaining) => write!(
+                f,
+                "Too many wrong PINs, wait {} seconds before trying again",
+                remaining.as_secs().max(1)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LoginError {}
+
+/// The session ID typed in by a player, which may have been issued under an earlier
+/// session ID format.
+fn find_session_id(state: &State, typed_id: &str) -> Result<SessionId, LoginError> {
+    state
+        .session_id_format
+        .find(typed_id, |id| state.sessions.contains_key(id))
+        .map_err(LoginError::InvalidSessionId)
+}
+
+pub async fn handle(
+    state: &mut State,
+    typed_id: &str,
+    pin: &str,
+    member_name: MemberName,
+    response: oneshot::Sender<Result<Joined, LoginError>>,
+) {
+    let result = find_session_id(state, typed_id).and_then(|id| {
+        check_pin(state, &id, pin)?;
+        Ok(join::join(state, id, member_name))
+    });
+    let is_ok = result.is_ok();
+    response.send(result).ok();
+    if is_ok {
+        state.writer.send(state.serialize()).await.ok();
+    }
+}
+
+fn check_pin(state: &mut State, id: &SessionId, pin: &str) -> Result<(), LoginError> {
+    let team_session = state.sessions.get(id).ok_or(LoginError::UnknownSessionId)?;
+    let Some(pin_hash) = &team_session.pin else {
+        return Ok(());
+    };
+
+    let now = Instant::now();
+    let limiter = state.login_limiters.entry(*id).or_default();
+    if let Some(remaining) = limiter.check(&state.login_limits, now) {
+        return Err(LoginError::LockedOut(remaining));
+    }
+    let is_correct = pin_hash.matches(pin);
+    limiter.record(&state.login_limits, now, is_correct);
+    if !is_correct {
+        tracing::warn!("Wrong PIN entered for SessionId={id}");
+        return Err(LoginError::WrongPin);
+    }
+    Ok(())
+}
+
+pub async fn handle_reset_pin(
+    state: &mut State,
+    typed_id: &str,
+    pin: Option<PinHash>,
+    response: oneshot::Sender<Result<TeamName, LoginError>>,
+) {
+    let id = match find_session_id(state, typed_id) {
+        Ok(id) => id,
+        Err(e) => {
+            response.send(Err(e)).ok();
+            return;
+        }
+    };
+    let Some(team_session) = state.sessions.get_mut(&id) else {
+        response.send(Err(LoginError::UnknownSessionId)).ok();
+        return;
+    };
+    tracing::info!(
+        "Reset PIN for TeamName={} SessionId={id}",
+        team_session.name
+    );
+    team_session.pin = pin;
+    team_session.pin_generation += 1;
+    let generation = team_session.pin_generation;
+    response.send(Ok(team_session.name.clone())).ok();
+    state.pin_generations.send_modify(|generations| {
+        generations.insert(id, generation);
+    });
+    state.login_limiters.remove(&id);
+    state.writer.send(state.serialize()).await.ok();
+}
@@ -1,26 +1,93 @@
 use {
     self::{
-        current_clue::CurrentClueError, leader_board::LeaderboardRow, new_session::NewSessionError,
+        announce::AnnounceError,
+        anomalies::AnomalyReport,
+        answer::{AnswerOutcome, Guess},
+        arrangements::HuntArrangements,
+        current_clue::CurrentClueError,
+        help::{HelpError, HelpRequest},
+        join::{JoinError, Joined},
+        leader_board::Leaderboard,
+        login::LoginError,
+        new_session::NewSessionError,
+        photo::{PendingPhoto, PhotoError},
+        reload::{HuntReload, ReloadError},
+        spectator::SpectatorView,
+        statistics::StatisticsReport,
+        team_codes::TeamCodesError,
+        team_info::TeamInfo,
     },
-    crate::state::TeamName,
+    crate::{
+        export::results::Results,
+        state::{
+            TeamName,
+            hunt::{HuntId, UnknownHunt},
+            member::{MemberId, MemberName},
+            pin::PinHash,
+        },
+    },
+    std::time::{Duration, SystemTime},
     tokio::sync::oneshot,
-    treasure_hunt_core::{clues::ClueView, session::SessionId},
+    treasure_hunt_core::{
+        clues::ClueView,
+        session::{SessionId, breakdown::ScoreBreakdown},
+    },
 };
 
+pub mod announce;
+pub mod anomalies;
 pub mod answer;
+pub mod arrangements;
 pub mod current_clue;
+pub mod freeze;
+pub mod help;
 pub mod hint;
+pub mod join;
 pub mod leader_board;
+pub mod login;
 pub mod new_session;
+pub mod photo;
+pub mod progress;
+pub mod reload;
+pub mod results;
+pub mod spectator;
+pub mod statistics;
+pub mod team_codes;
+pub mod team_info;
 
-pub type ClueOrScore = Either<ClueView, i32>;
+/// The current clue with how long the team is locked out of answering it, or the final score.
+pub type ClueOrScore = Either<(ClueView, Option<Duration>), i32>;
 
 /// Commands the app can send to the state
 #[derive(Debug)]
 pub enum Command {
     NewSession {
         team_name: TeamName,
-        response: oneshot::Sender<Result<SessionId, NewSessionError>>,
+        /// The default hunt is used if none is given.
+        hunt: Option<HuntId>,
+        member_name: MemberName,
+        pin: Option<PinHash>,
+        response: oneshot::Sender<Result<Joined, NewSessionError>>,
+    },
+    /// Join a team using its invite code.
+    JoinTeam {
+        invite_code: String,
+        member_name: MemberName,
+        response: oneshot::Sender<Result<Joined, JoinError>>,
+    },
+    /// Check the PIN (if any) for logging in to a team's session.
+    /// Log in to the team whose session ID was typed in as `typed_id`.
+    Login {
+        typed_id: String,
+        pin: String,
+        member_name: MemberName,
+        response: oneshot::Sender<Result<Joined, LoginError>>,
+    },
+    /// Set or remove a team's PIN.
+    ResetPin {
+        typed_id: String,
+        pin: Option<PinHash>,
+        response: oneshot::Sender<Result<TeamName, LoginError>>,
     },
     GetCurrentClue {
         id: SessionId,
@@ -28,21 +95,120 @@ pub enum Command {
     },
     HintCurrentClue {
         id: SessionId,
+        member: MemberId,
     },
     RevealCurrentItem {
         id: SessionId,
+        member: MemberId,
     },
     SkipClue {
         id: SessionId,
+        member: MemberId,
     },
     AnswerCurrentClue {
         id: SessionId,
-        guess: String,
-        response: oneshot::Sender<Result<Option<i32>, CurrentClueError>>,
+        member: MemberId,
+        guess: Guess,
+        response: oneshot::Sender<Result<AnswerOutcome, CurrentClueError>>,
     },
+    /// The leaderboard of the given hunt, or of the logged-in team's hunt.
     Leaderboard {
-        maybe_id: String,
-        response: oneshot::Sender<(Vec<LeaderboardRow>, Option<TeamName>)>,
+        hunt: Option<HuntId>,
+        maybe_id: Option<SessionId>,
+        /// Ignore any freeze, e.g. for admins.
+        live: bool,
+        response: oneshot::Sender<Result<Leaderboard, UnknownHunt>>,
+    },
+    /// Ask the game master for help with the current clue.
+    RequestHelp {
+        id: SessionId,
+        member: MemberId,
+        message: String,
+        response: oneshot::Sender<Result<(), HelpError>>,
+    },
+    /// Requests for help the game master has yet to reply to.
+    HelpQueue {
+        response: oneshot::Sender<Vec<HelpRequest>>,
+    },
+    /// Reply to a team's requests for help with a clue, charging them `points`.
+    ReplyToHelp {
+        id: SessionId,
+        clue_code: [u8; 32],
+        message: String,
+        points: i32,
+        response: oneshot::Sender<Result<TeamName, HelpError>>,
+    },
+    /// Answer the current clue with a photo saved as `file`, for the game master to review.
+    SubmitPhoto {
+        id: SessionId,
+        member: MemberId,
+        file: String,
+        response: oneshot::Sender<Result<(), PhotoError>>,
+    },
+    /// Photos the game master has yet to review.
+    PhotoQueue {
+        response: oneshot::Sender<Vec<PendingPhoto>>,
+    },
+    /// Approve (solving the clue) or reject a team's photo.
+    ReviewPhoto {
+        id: SessionId,
+        clue_code: [u8; 32],
+        approved: bool,
+        response: oneshot::Sender<Result<TeamName, PhotoError>>,
+    },
+    /// Show a message at the top of every page until it `expires`, to the given teams
+    /// or to everyone if none are given.
+    Announce {
+        message: String,
+        expires: Option<SystemTime>,
+        teams: Vec<SessionId>,
+        response: oneshot::Sender<Result<(), AnnounceError>>,
+    },
+    RemoveAnnouncement {
+        id: u64,
+        response: oneshot::Sender<()>,
+    },
+    /// What to show on the big screen for the given hunt, or for the default hunt.
+    Spectator {
+        hunt: Option<HuntId>,
+        response: oneshot::Sender<Result<SpectatorView, UnknownHunt>>,
+    },
+    /// Start or end showing the teams of a hunt, by default the first, the standings
+    /// from when its leaderboard was frozen.
+    FreezeLeaderboard {
+        hunt: Option<HuntId>,
+        freeze: bool,
+        response: oneshot::Sender<Result<Option<SystemTime>, UnknownHunt>>,
+    },
+    TeamCodes {
+        id: SessionId,
+        response: oneshot::Sender<Result<(TeamName, Vec<String>), TeamCodesError>>,
+    },
+    TeamInfo {
+        id: SessionId,
+        response: oneshot::Sender<Result<TeamInfo, CurrentClueError>>,
+    },
+    /// Read the clue files again and update the clues in all sessions.
+    ReloadClues {
+        confirm_answer_changes: bool,
+        response: oneshot::Sender<Result<Vec<HuntReload>, ReloadError>>,
+    },
+    /// The precomputed arrangements of each hunt, e.g. for printing route sheets.
+    Arrangements {
+        response: oneshot::Sender<Vec<HuntArrangements>>,
+    },
+    AnomalyReport {
+        response: oneshot::Sender<AnomalyReport>,
+    },
+    Statistics {
+        response: oneshot::Sender<StatisticsReport>,
+    },
+    Results {
+        response: oneshot::Sender<Results>,
+    },
+    ScoreBreakdown {
+        id: SessionId,
+        response: oneshot::Sender<Result<(TeamName, ScoreBreakdown), CurrentClueError>>,
     },
 }
 
@@ -1,18 +1,30 @@
 use {
-    crate::state::{State, TeamName, TeamSession},
+    crate::state::{
+        State, TeamName, TeamSession,
+        command::join::{self, Joined},
+        hunt::HuntId,
+        member::{self, MemberName},
+        pin::PinHash,
+    },
     std::fmt,
     tokio::sync::oneshot,
-    treasure_hunt_core::session::{Session, SessionId},
+    treasure_hunt_core::session::Session,
 };
 
 #[derive(Debug)]
 pub enum NewSessionError {
     DuplicateTeamName,
+    NoSessionIdsLeft,
+    UnknownHunt,
 }
 
 impl fmt::Display for NewSessionError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str("Duplicate team name")
+        match self {
+            Self::DuplicateTeamName => f.write_str("Duplicate team name"),
+            Self::NoSessionIdsLeft => f.write_str("No more teams can register"),
+            Self::UnknownHunt => f.write_str("Unknown hunt"),
+        }
     }
 }
 
@@ -21,20 +33,66 @@ impl std::error::Error for NewSessionError {}
 pub async fn handle(
     state: &mut State,
     team_name: TeamName,
-    response: oneshot::Sender<Result<SessionId, NewSessionError>>,
+    hunt: Option<HuntId>,
+    member_name: MemberName,
+    pin: Option<PinHash>,
+    response: oneshot::Sender<Result<Joined, NewSessionError>>,
 ) {
-    if state.team_names.contains(&team_name) {
+    let Some(id) = state
+        .session_id_format
+        .generate(|id| state.sessions.contains_key(id))
+    else {
+        tracing::error!("Failed to generate a unique session ID");
+        response.send(Err(NewSessionError::NoSessionIdsLeft)).ok();
+        return;
+    };
+    let Some(hunt) = state.hunt(hunt.as_ref()) else {
+        response.send(Err(NewSessionError::UnknownHunt)).ok();
+        return;
+    };
+    let hunt_id = hunt.id.clone();
+    let others: Vec<&TeamSession> = state
+        .sessions
+        .values()
+        .filter(|team_session| team_session.hunt == hunt_id)
+        .collect();
+    // Team names only need to be unique within a hunt.
+    if others
+        .iter()
+        .any(|team_session| team_session.name == team_name)
+    {
         response.send(Err(NewSessionError::DuplicateTeamName)).ok();
         return;
     }
-    let clues = state.clues.next().expect("The iterator is never empty");
-    let session = Session::new(clues);
-    let id = session.id;
-    response.send(Ok(id)).ok();
-    tracing::info!("Added new session. TeamName={team_name} SessionId={id}");
+    let upcoming_locations: Vec<Vec<&str>> = others
+        .iter()
+        .map(|team_session| team_session.session.upcoming_locations())
+        .collect();
+    let clues = hunt.next_clues(&upcoming_locations);
+    let mut session = Session::new(id, clues);
+    if let Some(late_join_credit) = &state.late_join_credit {
+        let mut scores: Vec<i32> = others
+            .iter()
+            .map(|team_session| team_session.session.total_score())
+            .collect();
+        let credit = late_join_credit.credit(&mut scores);
+        if credit > 0 {
+            tracing::info!("Giving {credit} points to late team. TeamName={team_name}");
+            session.grant_credit(credit);
+        }
+    }
+    let invite_code = loop {
+        let code = member::generate_invite_code();
+        if !state.sessions.values().any(|s| s.invite_code == code) {
+            break code;
+        }
+    };
+    tracing::info!("Added new session. TeamName={team_name} SessionId={id} Hunt={hunt_id}");
     state.team_names.insert(team_name.clone());
-    state
-        .sessions
-        .insert(id, TeamSession::new(team_name, session));
+    state.sessions.insert(
+        id,
+        TeamSession::new(team_name, hunt_id, session, pin, invite_code),
+    );
+    response.send(Ok(join::join(state, id, member_name))).ok();
     state.writer.send(state.serialize()).await.ok();
 }
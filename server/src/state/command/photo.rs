@@ -0,0 +1,124 @@
+use {
+    crate::state::{
+        State, TeamName,
+        member::{MemberId, MemberName},
+    },
+    std::{fmt, time::SystemTime},
+    tokio::sync::oneshot,
+    treasure_hunt_core::session::SessionId,
+};
+
+#[derive(Debug)]
+pub enum PhotoError {
+    UnknownSessionId,
+    /// The team's current clue is not answered with a photo.
+    NotPhotoClue,
+    /// The team has no photo waiting for review for that clue.
+    NotPending,
+}
+
+impl fmt::Display for PhotoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownSessionId => f.write_str("Unknown session ID"),
+            Self::NotPhotoClue => f.write_str("This clue is not answered with a photo"),
+            Self::NotPending => f.write_str("That photo has already been reviewed"),
+        }
+    }
+}
+
+impl std::error::Error for PhotoError {}
+
+/// A photo waiting in the game master's queue.
+#[derive(Debug)]
+pub struct PendingPhoto {
+    pub session_id: SessionId,
+    pub team_name: TeamName,
+    pub clue_number: usize,
+    pub clue_id: String,
+    pub clue_code: [u8; 32],
+    pub file: String,
+    pub member: Option<String>,
+    pub time: SystemTime,
+}
+
+pub async fn handle_submit(
+    state: &mut State,
+    id: &SessionId,
+    member: MemberId,
+    file: String,
+    response: oneshot::Sender<Result<(), PhotoError>>,
+) {
+    let Some(team_session) = state.sessions.get_mut(id) else {
+        response.send(Err(PhotoError::UnknownSessionId)).ok();
+        return;
+    };
+    let member_name = team_session.members.get(member).map(MemberName::as_str);
+    if team_session
+        .session
+        .submit_photo(&file, member_name)
+        .is_none()
+    {
+        response.send(Err(PhotoError::NotPhotoClue)).ok();
+        return;
+    }
+    tracing::info!("{} submitted photo {file}", team_session.name);
+    response.send(Ok(())).ok();
+    state.notify_team(*id, member, "sent a photo to the game master.");
+    state.writer.send(state.serialize()).await.ok();
+}
+
+/// All photos waiting for review, oldest first.
+pub fn handle_queue(state: &State, response: oneshot::Sender<Vec<PendingPhoto>>) {
+    let mut photos: Vec<PendingPhoto> = state
+        .sessions
+        .iter()
+        .flat_map(|(id, team_session)| {
+            team_session
+                .session
+                .pending_photos()
+                .into_iter()
+                .map(move |photo| PendingPhoto {
+                    session_id: *id,
+                    team_name: team_session.name.clone(),
+                    clue_number: photo.number,
+                    clue_id: photo.clue.id.clone(),
+                    clue_code: photo.clue.code,
+                    file: photo.file.into(),
+                    member: photo.member.map(String::from),
+                    time: photo.time,
+                })
+        })
+        .collect();
+    photos.sort_by_key(|photo| photo.time);
+    response.send(photos).ok();
+}
+
+pub async fn handle_review(
+    state: &mut State,
+    id: &SessionId,
+    clue_code: &[u8; 32],
+    approved: bool,
+    response: oneshot::Sender<Result<TeamName, PhotoError>>,
+) {
+    let Some(team_session) = state.sessions.get_mut(id) else {
+        response.send(Err(PhotoError::UnknownSessionId)).ok();
+        return;
+    };
+    if !team_session.session.review_photo(clue_code, approved) {
+        response.send(Err(PhotoError::NotPending)).ok();
+        return;
+    }
+    let team_name = team_session.name.clone();
+    let message = if approved {
+        tracing::info!("Approved photo from {team_name}");
+        state.notify_spectators(*id, clue_code);
+        "The game master approved your photo!"
+    } else {
+        tracing::info!("Rejected photo from {team_name}");
+        "The game master rejected your photo. Try that clue again later."
+    };
+    response.send(Ok(team_name)).ok();
+    state.notify_whole_team(*id, String::from(message));
+    state.writer.send(state.serialize()).await.ok();
+}
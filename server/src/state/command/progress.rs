@@ -0,0 +1,23 @@
+use {
+    crate::state::{State, TeamName, command::CurrentClueError},
+    tokio::sync::oneshot,
+    treasure_hunt_core::session::{SessionId, breakdown::ScoreBreakdown},
+};
+
+pub fn handle(
+    state: &State,
+    id: &SessionId,
+    response: oneshot::Sender<Result<(TeamName, ScoreBreakdown), CurrentClueError>>,
+) {
+    let result = state
+        .sessions
+        .get(id)
+        .map(|team_session| {
+            (
+                team_session.name.clone(),
+                team_session.session.score_breakdown(),
+            )
+        })
+        .ok_or(CurrentClueError::UnknownSessionId);
+    response.send(result).ok();
+}
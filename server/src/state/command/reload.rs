@@ -0,0 +1,107 @@
+use {
+    crate::state::{
+        State,
+        hunt::{HuntId, HuntSummary},
+    },
+    std::{fmt, io},
+    tokio::sync::oneshot,
+    treasure_hunt_core::clues::reload::ClueDiff,
+};
+
+#[derive(Debug)]
+pub struct HuntReload {
+    pub hunt: HuntSummary,
+    pub diff: ClueDiff,
+}
+
+#[derive(Debug)]
+pub enum ReloadError {
+    Io {
+        hunt: HuntId,
+        error: io::Error,
+    },
+    /// Nothing was changed because some answers would change.
+    AnswersChanged(Vec<HuntReload>),
+}
+
+impl fmt::Display for ReloadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { hunt, error } => {
+                write!(f, "Could not load the clues for hunt {hunt}: {error}")
+            }
+            Self::AnswersChanged(reloads) => {
+                let ids: Vec<&str> = reloads
+                    .iter()
+                    .flat_map(|reload| &reload.diff.changed_answer)
+                    .map(String::as_str)
+                    .collect();
+                write!(
+                    f,
+                    "The answers to clues {} would change, so nothing was reloaded",
+                    ids.join(", ")
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReloadError {}
+
+pub async fn handle(
+    state: &mut State,
+    confirm_answer_changes: bool,
+    response: oneshot::Sender<Result<Vec<HuntReload>, ReloadError>>,
+) {
+    let result = reload(state, confirm_answer_changes);
+    let is_changed = result
+        .as_ref()
+        .is_ok_and(|reloads| reloads.iter().any(|reload| !reload.diff.is_empty()));
+    response.send(result).ok();
+    if is_changed {
+        state.writer.send(state.serialize()).await.ok();
+    }
+}
+
+fn reload(state: &mut State, confirm_answer_changes: bool) -> Result<Vec<HuntReload>, ReloadError> {
+    let mut loaded = Vec::new();
+    for hunt in &state.hunts {
+        let (clues, arrangements) = hunt.load_clues().map_err(|error| ReloadError::Io {
+            hunt: hunt.id.clone(),
+            error,
+        })?;
+        let in_use = state
+            .sessions
+            .values()
+            .filter(|team_session| team_session.hunt == hunt.id)
+            .flat_map(|team_session| team_session.session.clues().map(|(clue, _)| clue));
+        let diff = ClueDiff::new(hunt.clues().0.iter().chain(in_use), &clues);
+        let reload = HuntReload {
+            hunt: hunt.summary(),
+            diff,
+        };
+        loaded.push((clues, arrangements, reload));
+    }
+
+    let is_answer_changed = loaded
+        .iter()
+        .any(|(_, _, reload)| !reload.diff.changed_answer.is_empty());
+    if is_answer_changed && !confirm_answer_changes {
+        let reloads = loaded.into_iter().map(|(_, _, reload)| reload).collect();
+        return Err(ReloadError::AnswersChanged(reloads));
+    }
+
+    for (hunt, (clues, arrangements, reload)) in state.hunts.iter_mut().zip(&loaded) {
+        if reload.diff.is_empty() {
+            continue;
+        }
+        tracing::info!("Reloading clues for Hunt={}: {:?}", hunt.id, reload.diff);
+        for team_session in state.sessions.values_mut() {
+            if team_session.hunt == hunt.id {
+                team_session.session.update_clues(clues);
+            }
+        }
+        hunt.replace_clues(clues.clone(), arrangements.clone());
+    }
+    Ok(loaded.into_iter().map(|(_, _, reload)| reload).collect())
+}
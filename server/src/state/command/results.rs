@@ -0,0 +1,5 @@
+use {crate::export::results::Results, crate::state::State, tokio::sync::oneshot};
+
+pub fn handle(state: &State, response: oneshot::Sender<Results>) {
+    response.send(Results::new(state.sessions.values())).ok();
+}
@@ -0,0 +1,116 @@
+use {
+    crate::state::{
+        State,
+        command::leader_board::{self, Leaderboard},
+        hunt::{HuntId, UnknownHunt},
+        spectator,
+    },
+    serde::Serialize,
+    std::{collections::BTreeMap, time::SystemTime},
+    tokio::sync::oneshot,
+    treasure_hunt_core::session::history::{GuessOutcome, HistoryEvent},
+};
+
+/// Number of solves listed on the spectator page.
+const N_RECENT_SOLVES: usize = 10;
+
+#[derive(Debug)]
+pub struct SpectatorView {
+    /// The leaderboard teams see, so frozen if theirs is.
+    pub leaderboard: Leaderboard,
+    /// The latest solves first. Solves after the leaderboard was frozen are left out.
+    pub recent_solves: Vec<RecentSolve>,
+    /// Every location in the hunt, the busiest first.
+    pub occupancy: Vec<LocationOccupancy>,
+}
+
+#[derive(Debug)]
+pub struct RecentSolve {
+    pub time: SystemTime,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LocationOccupancy {
+    pub location: String,
+    /// Number of teams whose current clue is at this location.
+    pub teams: usize,
+}
+
+pub fn handle(
+    state: &State,
+    hunt: Option<HuntId>,
+    response: oneshot::Sender<Result<SpectatorView, UnknownHunt>>,
+) {
+    let Some(hunt) = state.hunt(hunt.as_ref()) else {
+        response.send(Err(UnknownHunt)).ok();
+        return;
+    };
+    let leaderboard = leader_board::leaderboard(state, hunt, None, false);
+    let team_sessions: Vec<_> = state
+        .sessions
+        .values()
+        .filter(|team_session| team_session.hunt == hunt.id)
+        .collect();
+
+    let mut recent_solves: Vec<RecentSolve> = team_sessions
+        .iter()
+        .flat_map(|team_session| {
+            team_session
+                .session
+                .history()
+                .iter()
+                .filter(|entry| {
+                    matches!(
+                        entry.event,
+                        HistoryEvent::Guess {
+                            outcome: GuessOutcome::Correct,
+                            ..
+                        } | HistoryEvent::PhotoReviewed { approved: true }
+                    )
+                })
+                .filter(|entry| {
+                    leaderboard
+                        .frozen_since
+                        .is_none_or(|since| entry.time < since)
+                })
+                .filter_map(|entry| {
+                    let solve = spectator::describe_solve(&team_session.session, &entry.clue_code)?;
+                    Some(RecentSolve {
+                        time: entry.time,
+                        message: format!("{} solved {solve}!", team_session.name),
+                    })
+                })
+        })
+        .collect();
+    recent_solves.sort_by(|a, b| b.time.cmp(&a.time));
+    recent_solves.truncate(N_RECENT_SOLVES);
+
+    let mut counts: BTreeMap<&str, usize> = hunt
+        .clues()
+        .0
+        .iter()
+        .map(|clue| (clue.location.as_str(), 0))
+        .collect();
+    for team_session in &team_sessions {
+        if let Some(location) = team_session.session.current_location() {
+            *counts.entry(location).or_default() += 1;
+        }
+    }
+    let mut occupancy: Vec<LocationOccupancy> = counts
+        .into_iter()
+        .map(|(location, teams)| LocationOccupancy {
+            location: location.into(),
+            teams,
+        })
+        .collect();
+    // Stable, so locations with the same number of teams stay in alphabetical order.
+    occupancy.sort_by(|a, b| b.teams.cmp(&a.teams));
+
+    let view = SpectatorView {
+        leaderboard,
+        recent_solves,
+        occupancy,
+    };
+    response.send(Ok(view)).ok();
+}
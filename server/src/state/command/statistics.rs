@@ -0,0 +1,63 @@
+use {
+    crate::state::{State, TeamName, hunt::HuntSummary},
+    tokio::sync::oneshot,
+    treasure_hunt_core::{
+        analytics::{self, ClueStatistics, TimelineEntry},
+        session::SessionId,
+    },
+};
+
+/// Statistics for each hunt, in the order the hunts are configured.
+pub type StatisticsReport = Vec<HuntStatistics>;
+
+#[derive(Debug)]
+pub struct HuntStatistics {
+    pub hunt: HuntSummary,
+    pub clues: Vec<ClueStatistics>,
+    pub timelines: Vec<TeamTimeline>,
+}
+
+#[derive(Debug)]
+pub struct TeamTimeline {
+    pub team_name: TeamName,
+    pub session_id: SessionId,
+    pub entries: Vec<TimelineEntry>,
+}
+
+pub fn handle(state: &State, response: oneshot::Sender<StatisticsReport>) {
+    let report = state
+        .hunts
+        .iter()
+        .map(|hunt| {
+            let team_sessions: Vec<_> = state
+                .sessions
+                .values()
+                .filter(|team_session| team_session.hunt == hunt.id)
+                .collect();
+            let mut clues = analytics::clue_statistics(team_sessions.iter().map(|t| &t.session));
+            // List clues in the order of the clue file, then any which were removed from it.
+            clues.sort_by_key(|stats| {
+                hunt.clues()
+                    .0
+                    .iter()
+                    .position(|clue| clue.id == stats.clue_id)
+                    .unwrap_or(usize::MAX)
+            });
+            let mut timelines: Vec<TeamTimeline> = team_sessions
+                .iter()
+                .map(|team_session| TeamTimeline {
+                    team_name: team_session.name.clone(),
+                    session_id: team_session.session.id,
+                    entries: analytics::timeline(&team_session.session),
+                })
+                .collect();
+            timelines.sort_by(|a, b| a.team_name.to_string().cmp(&b.team_name.to_string()));
+            HuntStatistics {
+                hunt: hunt.summary(),
+                clues,
+                timelines,
+            }
+        })
+        .collect();
+    response.send(report).ok();
+}
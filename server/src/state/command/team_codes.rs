@@ -0,0 +1,45 @@
+use {
+    crate::state::{State, TeamName},
+    std::fmt,
+    tokio::sync::oneshot,
+    treasure_hunt_core::{clues::team_code::AnswerMode, session::SessionId},
+};
+
+#[derive(Debug)]
+pub enum TeamCodesError {
+    UnknownSessionId,
+    Disabled,
+}
+
+impl fmt::Display for TeamCodesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownSessionId => f.write_str("Unknown session ID"),
+            Self::Disabled => f.write_str("Team codes are not used in this game"),
+        }
+    }
+}
+
+impl std::error::Error for TeamCodesError {}
+
+pub fn handle(
+    state: &State,
+    id: &SessionId,
+    response: oneshot::Sender<Result<(TeamName, Vec<String>), TeamCodesError>>,
+) {
+    response.send(inner(state, id)).ok();
+}
+
+fn inner(state: &State, id: &SessionId) -> Result<(TeamName, Vec<String>), TeamCodesError> {
+    let AnswerMode::TeamSpecific(key) = &state.answer_mode else {
+        return Err(TeamCodesError::Disabled);
+    };
+    let team_session = state
+        .sessions
+        .get(id)
+        .ok_or(TeamCodesError::UnknownSessionId)?;
+    Ok((
+        team_session.name.clone(),
+        team_session.session.team_codes(key),
+    ))
+}
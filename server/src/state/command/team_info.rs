@@ -0,0 +1,31 @@
+use {
+    crate::state::{State, TeamName, command::CurrentClueError, hunt::HuntId, member::MemberName},
+    tokio::sync::oneshot,
+    treasure_hunt_core::session::SessionId,
+};
+
+#[derive(Debug)]
+pub struct TeamInfo {
+    pub team_name: TeamName,
+    pub invite_code: String,
+    pub members: Vec<MemberName>,
+    pub hunt: HuntId,
+}
+
+pub fn handle(
+    state: &State,
+    id: &SessionId,
+    response: oneshot::Sender<Result<TeamInfo, CurrentClueError>>,
+) {
+    let info = state
+        .sessions
+        .get(id)
+        .map(|team_session| TeamInfo {
+            team_name: team_session.name.clone(),
+            invite_code: team_session.invite_code.clone(),
+            members: team_session.members.clone(),
+            hunt: team_session.hunt.clone(),
+        })
+        .ok_or(CurrentClueError::UnknownSessionId);
+    response.send(info).ok();
+}
@@ -0,0 +1,80 @@
+//! Freezing the leaderboard of each hunt near its end, so that the final standings
+//! are a surprise until an admin reveals them.
+
+use {
+    crate::{
+        config::Config,
+        state::{TeamSession, command::Command, hunt::HuntId},
+    },
+    std::{collections::HashMap, time::Duration, time::SystemTime},
+    tokio::{
+        sync::{mpsc, oneshot},
+        task::JoinHandle,
+    },
+    treasure_hunt_core::session::{SessionId, standing::Standing},
+};
+
+/// The standings of a hunt's teams when its leaderboard was frozen.
+#[derive(Debug, Clone)]
+pub struct FrozenLeaderboard {
+    pub since: SystemTime,
+    pub standings: HashMap<SessionId, Standing>,
+}
+
+impl FrozenLeaderboard {
+    pub fn new(sessions: &HashMap<SessionId, TeamSession>, hunt: &HuntId) -> Self {
+        Self {
+            since: SystemTime::now(),
+            standings: sessions
+                .iter()
+                .filter(|(_, team_session)| &team_session.hunt == hunt)
+                .map(|(id, team_session)| (*id, team_session.session.standing()))
+                .collect(),
+        }
+    }
+}
+
+/// Freezes the leaderboard of a hunt when its freeze window starts.
+pub struct FreezeTimer {
+    hunt: HuntId,
+    start: SystemTime,
+    sender: mpsc::Sender<Command>,
+}
+
+impl FreezeTimer {
+    /// Returns `None` if no freeze window is configured for the hunt, or if it has already
+    /// started. A server restarted during the window keeps any persisted frozen leaderboard.
+    pub fn new(config: &Config, hunt: HuntId, sender: mpsc::Sender<Command>) -> Option<Self> {
+        let end_time = config.hunt_settings(hunt.as_str()).end_time?.inner;
+        let minutes = config.leaderboard_freeze_minutes?;
+        let start = end_time.checked_sub(Duration::from_secs(60 * minutes))?;
+        if start <= SystemTime::now() {
+            tracing::warn!("The leaderboard freeze window has already started Hunt={hunt}");
+            return None;
+        }
+        Some(Self {
+            hunt,
+            start,
+            sender,
+        })
+    }
+
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let wait = self
+                .start
+                .duration_since(SystemTime::now())
+                .unwrap_or_default();
+            tokio::time::sleep(wait).await;
+            let (tx, rx) = oneshot::channel();
+            let command = Command::FreezeLeaderboard {
+                hunt: Some(self.hunt),
+                freeze: true,
+                response: tx,
+            };
+            if self.sender.send(command).await.is_ok() {
+                rx.await.ok();
+            }
+        })
+    }
+}
@@ -0,0 +1,157 @@
+//! Limits on how quickly a team can submit answers, to prevent brute-forcing short answers.
+
+use {
+    crate::config::GuessLimitConfig,
+    std::{
+        collections::VecDeque,
+        time::{Duration, Instant},
+    },
+};
+
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Default)]
+pub struct GuessLimiter {
+    recent_guesses: VecDeque<Instant>,
+    wrong_in_a_row: u32,
+    n_lockouts: u32,
+    locked_until: Option<Instant>,
+}
+
+impl GuessLimiter {
+    /// Returns the remaining lockout time if the team is not allowed to guess right now.
+    pub fn check(&mut self, config: &GuessLimitConfig, now: Instant) -> Option<Duration> {
+        if let Some(until) = self.locked_until {
+            if until > now {
+                return Some(until - now);
+            }
+            self.locked_until = None;
+        }
+
+        while self
+            .recent_guesses
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= RATE_WINDOW)
+        {
+            self.recent_guesses.pop_front();
+        }
+        let is_rate_limited = config.max_guesses_per_minute > 0;
+        if is_rate_limited && self.recent_guesses.len() >= config.max_guesses_per_minute {
+            let oldest = self.recent_guesses.front()?;
+            return Some(RATE_WINDOW.saturating_sub(now.duration_since(*oldest)));
+        }
+
+        None
+    }
+
+    /// Record a guess which was allowed by `check`.
+    pub fn record(&mut self, config: &GuessLimitConfig, now: Instant, is_correct: bool) {
+        self.recent_guesses.push_back(now);
+        if is_correct {
+            self.wrong_in_a_row = 0;
+            self.n_lockouts = 0;
+            return;
+        }
+
+        self.wrong_in_a_row += 1;
+        if self.wrong_in_a_row >= config.wrong_guesses_before_lockout {
+            let multiplier = 2u64.saturating_pow(self.n_lockouts);
+            let seconds = config
+                .lockout_seconds
+                .saturating_mul(multiplier)
+                .min(config.max_lockout_seconds);
+            self.locked_until = Some(now + Duration::from_secs(seconds));
+            self.wrong_in_a_row = 0;
+            self.n_lockouts += 1;
+        }
+    }
+}
+
+#[test]
+fn test_guess_limiter() {
+    let config = GuessLimitConfig {
+        max_guesses_per_minute: 4,
+        wrong_guesses_before_lockout: 3,
+        lockout_seconds: 10,
+        max_lockout_seconds: 25,
+    };
+    let mut limiter = GuessLimiter::default();
+    let start = Instant::now();
+    let secs = |s| start + Duration::from_secs(s);
+
+    // Lockouts escalate after repeated wrong guesses
+    for s in 0..3 {
+        assert_eq!(limiter.check(&config, secs(s)), None);
+        limiter.record(&config, secs(s), false);
+    }
+    assert_eq!(
+        limiter.check(&config, secs(5)),
+        Some(Duration::from_secs(7))
+    );
+    for s in 12..15 {
+        assert_eq!(limiter.check(&config, secs(s * 10)), None);
+        limiter.record(&config, secs(s * 10), false);
+    }
+    assert_eq!(
+        limiter.check(&config, secs(140)),
+        Some(Duration::from_secs(20)),
+        "Second lockout is twice as long"
+    );
+    for s in 17..20 {
+        limiter.check(&config, secs(s * 10));
+        limiter.record(&config, secs(s * 10), false);
+    }
+    assert_eq!(
+        limiter.check(&config, secs(190)),
+        Some(Duration::from_secs(25)),
+        "Lockout is capped"
+    );
+
+    // A correct answer resets the escalation
+    limiter.check(&config, secs(300));
+    limiter.record(&config, secs(300), true);
+    for s in 31..34 {
+        limiter.check(&config, secs(s * 10));
+        limiter.record(&config, secs(s * 10), false);
+    }
+    assert_eq!(
+        limiter.check(&config, secs(330)),
+        Some(Duration::from_secs(10))
+    );
+
+    // Too many guesses in a minute are rate limited
+    let mut limiter = GuessLimiter::default();
+    for s in 0..4 {
+        assert_eq!(limiter.check(&config, secs(s)), None);
+        limiter.record(&config, secs(s), s % 2 == 0);
+    }
+    assert_eq!(
+        limiter.check(&config, secs(30)),
+        Some(Duration::from_secs(30))
+    );
+    assert_eq!(limiter.check(&config, secs(60)), None);
+
+    // A limit of 0 means guesses are not rate limited
+    let unlimited = GuessLimitConfig {
+        max_guesses_per_minute: 0,
+        ..config
+    };
+    let mut limiter = GuessLimiter::default();
+    for s in 0..10 {
+        assert_eq!(limiter.check(&unlimited, secs(s)), None);
+        limiter.record(&unlimited, secs(s), true);
+    }
+
+    // With 0 wrong answers before a lockout, every wrong answer locks the team out
+    let strict = GuessLimitConfig {
+        wrong_guesses_before_lockout: 0,
+        ..config
+    };
+    let mut limiter = GuessLimiter::default();
+    assert_eq!(limiter.check(&strict, secs(0)), None);
+    limiter.record(&strict, secs(0), false);
+    assert_eq!(
+        limiter.check(&strict, secs(0)),
+        Some(Duration::from_secs(10))
+    );
+}
@@ -0,0 +1,136 @@
+//! Hunts. One server can run several hunts at once (e.g. one for kids and one for
+//! adults), each with its own clues, arrangements and leaderboard. Hunts can also set
+//! their own waits before hints and skips and their own end time (see `HuntConfig`), and
+//! team names need only be unique within a hunt.
+
+use {
+    crate::config::HuntConfig,
+    serde::Serialize,
+    std::{
+        fmt, io,
+        path::{Path, PathBuf},
+    },
+    treasure_hunt_core::clues::{Clues, arrangement::Arrangements},
+};
+
+/// The hunt used when the config does not list any hunts, and for sessions
+/// persisted before there were multiple hunts.
+pub const DEFAULT_HUNT_ID: &str = "main";
+
+/// Identifies a hunt in URLs, so it is restricted to letters, digits, `-` and `_`.
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize)]
+pub struct HuntId(String);
+
+impl HuntId {
+    pub fn new(id: &str) -> anyhow::Result<Self> {
+        let is_valid = !id.is_empty()
+            && id.len() <= 32
+            && id
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_'));
+        if !is_valid {
+            anyhow::bail!("Invalid hunt ID {id:?}");
+        }
+        Ok(Self(id.into()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for HuntId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+pub struct Hunt {
+    pub id: HuntId,
+    pub name: String,
+    clues_path: PathBuf,
+    clues: Clues,
+    arrangements: Arrangements,
+}
+
+impl Hunt {
+    pub fn from_config(config: &HuntConfig) -> io::Result<Self> {
+        let id = HuntId::new(&config.id).map_err(io::Error::other)?;
+        let clues_path = PathBuf::from(&config.clues_path);
+        let (clues, arrangements) = read_clues(&clues_path)?;
+        Ok(Self {
+            id,
+            name: config.name.clone(),
+            clues_path,
+            clues,
+            arrangements,
+        })
+    }
+
+    /// The clues, in order, for the next team to register, given the upcoming
+    /// locations of the teams already playing this hunt.
+    pub fn next_clues(&self, others: &[Vec<&str>]) -> Clues {
+        self.arrangements.least_overlapping(others)
+    }
+
+    pub fn arrangements(&self) -> &Arrangements {
+        &self.arrangements
+    }
+
+    pub fn clues(&self) -> &Clues {
+        &self.clues
+    }
+
+    /// Read the clue file again and arrange the clues, without changing the hunt.
+    pub fn load_clues(&self) -> io::Result<(Clues, Arrangements)> {
+        read_clues(&self.clues_path)
+    }
+
+    /// Use new clues for teams which register from now on.
+    pub fn replace_clues(&mut self, clues: Clues, arrangements: Arrangements) {
+        self.clues = clues;
+        self.arrangements = arrangements;
+    }
+
+    pub fn summary(&self) -> HuntSummary {
+        HuntSummary {
+            id: self.id.clone(),
+            name: self.name.clone(),
+        }
+    }
+}
+
+/// Read the clues at `path` along with their arrangements.
+fn read_clues(path: &Path) -> io::Result<(Clues, Arrangements)> {
+    let clues = Clues::from_disk(path)?;
+    let arrangements = Arrangements::new(clues.clone())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    Ok((clues, arrangements))
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HuntSummary {
+    pub id: HuntId,
+    pub name: String,
+}
+
+#[derive(Debug)]
+pub struct UnknownHunt;
+
+impl fmt::Display for UnknownHunt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Unknown hunt")
+    }
+}
+
+impl std::error::Error for UnknownHunt {}
+
+#[test]
+fn test_hunt_id() {
+    assert_eq!(HuntId::new("kids-2").unwrap().as_str(), "kids-2");
+    assert!(HuntId::new("").is_err());
+    assert!(
+        HuntId::new("../admin").is_err(),
+        "Hunt IDs are safe in URLs"
+    );
+}
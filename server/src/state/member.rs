@@ -0,0 +1,80 @@
+//! Team members. Several players can join a team with its invite code, each with
+//! their own display name, so that actions can be attributed to whoever performed them.
+
+use {
+    rand::Rng, serde::Serialize, std::fmt, tokio::sync::broadcast,
+    treasure_hunt_core::session::SessionId,
+};
+
+/// Index of a member within their team.
+pub type MemberId = usize;
+
+/// Number of characters in an invite code.
+const INVITE_CODE_LEN: usize = 8;
+
+/// Characters used in invite codes, leaving out easily confused letters and digits.
+const INVITE_CODE_ALPHABET: &[u8; 32] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+pub struct MemberName(pub(super) String);
+
+impl MemberName {
+    pub fn new(name: &str) -> anyhow::Result<Self> {
+        let trimmed = name.trim();
+        if trimmed.is_empty() {
+            anyhow::bail!("Enter your name!");
+        }
+        if trimmed.len() > 30 {
+            anyhow::bail!("Name too long!");
+        }
+        Ok(MemberName(trimmed.into()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for MemberName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+pub fn generate_invite_code() -> String {
+    let mut rng = rand::rng();
+    (0..INVITE_CODE_LEN)
+        .map(|_| char::from(INVITE_CODE_ALPHABET[rng.random_range(0..INVITE_CODE_ALPHABET.len())]))
+        .collect()
+}
+
+/// Invite codes may be typed in either case, with spaces or dashes.
+pub fn normalize_invite_code(input: &str) -> String {
+    input
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-'))
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+/// Something a team member did, which is pushed to their teammates' open clue pages.
+#[derive(Debug, Clone)]
+pub struct TeamEvent {
+    /// The team to tell, or `None` for every team.
+    pub session_id: Option<SessionId>,
+    /// The member who did it, or `None` for the game master, whom every member hears.
+    pub member: Option<MemberId>,
+    pub message: String,
+}
+
+pub type TeamEvents = broadcast::Sender<TeamEvent>;
+
+#[test]
+fn test_invite_code() {
+    let code = generate_invite_code();
+    assert_eq!(code.len(), INVITE_CODE_LEN);
+    let typed = format!("{}-{}", &code[..4], &code[4..]).to_ascii_lowercase();
+    assert_eq!(normalize_invite_code(&typed), code);
+    assert!(MemberName::new("  ").is_err());
+    assert_eq!(MemberName::new(" Alice ").unwrap().as_str(), "Alice");
+}
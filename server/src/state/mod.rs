@@ -1,33 +1,87 @@
 use {
-    self::command::Command,
-    crate::config::Config,
+    self::{
+        announcement::{Announcement, Announcements},
+        command::Command,
+        freeze::FrozenLeaderboard,
+        guess_limit::GuessLimiter,
+        hunt::{Hunt, HuntId},
+        member::{MemberId, MemberName, TeamEvent, TeamEvents},
+        pin::{PinGenerations, PinHash},
+        serialization::PersistedState,
+        spectator::{SpectatorEvent, SpectatorEvents},
+    },
+    crate::config::{Config, GuessLimitConfig, LateJoinCreditConfig},
+    serde::Serialize,
     std::{
         collections::{HashMap, HashSet},
         fmt, io,
         path::Path,
     },
-    tokio::sync::mpsc,
+    tokio::sync::{broadcast, mpsc, watch},
     treasure_hunt_core::{
-        clues::{
-            Clues,
-            arrangement::{Arrangements, CluesGenerator},
-        },
-        session::{Session, SessionId},
+        clues::{scan::ScanKey, team_code::AnswerMode},
+        session::{Session, SessionId, SessionIdFormat},
     },
 };
 
+pub mod announcement;
 pub mod command;
+pub mod freeze;
+mod guess_limit;
+pub mod hunt;
+pub mod member;
+pub mod pin;
 mod serialization;
+pub mod spectator;
+pub mod watcher;
 pub mod writer;
 
 pub struct TeamSession {
     pub name: TeamName,
     pub session: Session,
+    pub pin: Option<PinHash>,
+    pub invite_code: String,
+    pub members: Vec<MemberName>,
+    pub hunt: HuntId,
+    /// How many times the PIN was reset. Session cookies issued before the last reset
+    /// are not accepted, so that a reset logs out whoever had the old PIN.
+    pub pin_generation: u32,
 }
 
 impl TeamSession {
-    pub fn new(name: TeamName, session: Session) -> Self {
-        Self { name, session }
+    pub fn new(
+        name: TeamName,
+        hunt: HuntId,
+        session: Session,
+        pin: Option<PinHash>,
+        invite_code: String,
+    ) -> Self {
+        Self {
+            name,
+            hunt,
+            session,
+            pin,
+            invite_code,
+            members: Vec::new(),
+            pin_generation: 0,
+        }
+    }
+
+    /// Add a member to the team. A member who joins again with the same name
+    /// (e.g. on another device) keeps their previous identity.
+    pub fn add_member(&mut self, name: MemberName) -> MemberId {
+        let existing = self
+            .members
+            .iter()
+            .position(|member| member.0.eq_ignore_ascii_case(&name.0));
+        existing.unwrap_or_else(|| {
+            self.members.push(name);
+            self.members.len() - 1
+        })
+    }
+
+    pub fn member_name(&self, member: MemberId) -> Option<&str> {
+        self.members.get(member).map(MemberName::as_str)
     }
 }
 
@@ -36,24 +90,76 @@ pub struct State {
     team_names: HashSet<TeamName>,
     channel: mpsc::Receiver<Command>,
     writer: mpsc::Sender<Result<String, serde_json::Error>>,
-    clues: CluesGenerator,
+    /// All hunts, in the order they are listed at registration.
+    hunts: Vec<Hunt>,
+    answer_mode: AnswerMode,
+    scan_key: Option<ScanKey>,
+    guess_limits: GuessLimitConfig,
+    guess_limiters: HashMap<SessionId, GuessLimiter>,
+    login_limits: GuessLimitConfig,
+    login_limiters: HashMap<SessionId, GuessLimiter>,
+    session_id_format: SessionIdFormat,
+    late_join_credit: Option<LateJoinCreditConfig>,
+    /// Standings shown to the teams of each hunt instead of the live ones near its end.
+    frozen_leaderboards: HashMap<HuntId, FrozenLeaderboard>,
+    /// Published so that routes can show them on every page without asking the state.
+    announcements: watch::Sender<Vec<Announcement>>,
+    /// Published so that routes can check session cookies without asking the state.
+    pin_generations: watch::Sender<HashMap<SessionId, u32>>,
+    events: TeamEvents,
+    spectator_events: SpectatorEvents,
 }
 
 impl State {
     pub fn new(config: &Config) -> io::Result<(Self, mpsc::Sender<Command>, writer::StateWriter)> {
-        let path = Path::new(&config.clues_path);
-        let clues = Clues::from_disk(path)?;
-        let iterator = Arrangements::new(clues).iterator();
+        config.session_ids.validate().map_err(io::Error::other)?;
+        let hunts = config
+            .hunts()
+            .iter()
+            .map(Hunt::from_config)
+            .collect::<io::Result<Vec<_>>>()?;
+        for (i, hunt) in hunts.iter().enumerate() {
+            if hunts[..i].iter().any(|other| other.id == hunt.id) {
+                return Err(io::Error::other(format!("Duplicate hunt ID {}", hunt.id)));
+            }
+        }
         let (sender, channel) = mpsc::channel(config.state_channel_size);
         let (writer_tx, writer_rx) = mpsc::channel(config.state_channel_size);
         let state_writer = writer::StateWriter::new(config, writer_rx);
-        let (sessions, team_names) = Self::load_persisted_state(config).unwrap_or_default();
+        let PersistedState {
+            sessions,
+            team_names,
+            frozen_leaderboards,
+            announcements,
+        } = Self::load_persisted_state(config).unwrap_or_default();
+        let answer_mode = match &config.team_codes_secret {
+            Some(secret) => AnswerMode::team_specific(secret),
+            None => AnswerMode::Shared,
+        };
+        let pin_generations = sessions
+            .iter()
+            .filter(|(_, team_session)| team_session.pin_generation > 0)
+            .map(|(id, team_session)| (*id, team_session.pin_generation))
+            .collect();
         let state = Self {
             sessions,
             team_names,
             channel,
             writer: writer_tx,
-            clues: iterator,
+            hunts,
+            answer_mode,
+            scan_key: config.scan_secret.as_deref().map(ScanKey::new),
+            guess_limits: config.guess_limits.clone(),
+            guess_limiters: HashMap::new(),
+            login_limits: config.login_limits.clone(),
+            login_limiters: HashMap::new(),
+            session_id_format: config.session_ids.clone(),
+            late_join_credit: config.late_join_credit.clone(),
+            frozen_leaderboards,
+            announcements: watch::Sender::new(announcements),
+            pin_generations: watch::Sender::new(pin_generations),
+            events: broadcast::channel(config.state_channel_size).0,
+            spectator_events: broadcast::channel(config.state_channel_size).0,
         };
         Ok((state, sender, state_writer))
     }
@@ -63,10 +169,87 @@ impl State {
         serde_json::to_string_pretty(&serializable)
     }
 
-    pub fn get_team_name(&self, maybe_id: &str) -> Option<&TeamName> {
-        let session_id = SessionId::new(maybe_id)?;
-        let session = self.sessions.get(&session_id)?;
-        Some(&session.name)
+    /// The channel on which team events are published, for routes to subscribe to.
+    pub fn events(&self) -> TeamEvents {
+        self.events.clone()
+    }
+
+    /// How many times each team's PIN was reset, for routes to check session cookies.
+    pub fn pin_generations(&self) -> PinGenerations {
+        self.pin_generations.subscribe()
+    }
+
+    /// The current announcements, for routes to show on every page.
+    pub fn announcements(&self) -> Announcements {
+        self.announcements.subscribe()
+    }
+
+    /// The channel on which spectator events are published, for the spectator page.
+    pub fn spectator_events(&self) -> SpectatorEvents {
+        self.spectator_events.clone()
+    }
+
+    /// Tell spectators that a team just solved the clue with the given code. Nothing is
+    /// sent while the team's leaderboard is frozen, to keep the final standings a surprise.
+    fn notify_spectators(&self, session_id: SessionId, clue_code: &[u8; 32]) {
+        let Some(team_session) = self.sessions.get(&session_id) else {
+            return;
+        };
+        if self.frozen_leaderboards.contains_key(&team_session.hunt) {
+            return;
+        }
+        let Some(solve) = spectator::describe_solve(&team_session.session, clue_code) else {
+            return;
+        };
+        let event = SpectatorEvent {
+            hunt: team_session.hunt.clone(),
+            message: format!("{} just solved {solve}!", team_session.name),
+        };
+        // Sending only fails if nobody is watching.
+        self.spectator_events.send(event).ok();
+    }
+
+    /// Tell the other members of a team what a member just did.
+    fn notify_team(&self, session_id: SessionId, member: MemberId, action: &str) {
+        let Some(team_session) = self.sessions.get(&session_id) else {
+            return;
+        };
+        let name = team_session.member_name(member).unwrap_or("A teammate");
+        let event = TeamEvent {
+            session_id: Some(session_id),
+            member: Some(member),
+            message: format!("{name} {action}"),
+        };
+        // Sending only fails if nobody is listening.
+        self.events.send(event).ok();
+    }
+
+    /// Tell every member of a team something from the game master.
+    fn notify_whole_team(&self, session_id: SessionId, message: String) {
+        let event = TeamEvent {
+            session_id: Some(session_id),
+            member: None,
+            message,
+        };
+        self.events.send(event).ok();
+    }
+
+    /// Tell every member of every team something from the game master.
+    fn notify_everyone(&self, message: String) {
+        let event = TeamEvent {
+            session_id: None,
+            member: None,
+            message,
+        };
+        self.events.send(event).ok();
+    }
+
+    /// The hunt with the given ID, or the default hunt if no ID is given.
+    fn hunt(&self, id: Option<&HuntId>) -> Option<&Hunt> {
+        match id {
+            Some(id) => self.hunts.iter().find(|hunt| &hunt.id == id),
+            None => self.hunts.first(),
+        }
     }
 
     pub fn spawn(mut self) -> tokio::task::JoinHandle<()> {
@@ -75,48 +258,218 @@ impl State {
                 match command {
                     Command::NewSession {
                         team_name,
+                        hunt,
+                        member_name,
+                        pin,
+                        response,
+                    } => {
+                        command::new_session::handle(
+                            &mut self,
+                            team_name,
+                            hunt,
+                            member_name,
+                            pin,
+                            response,
+                        )
+                        .await
+                    }
+                    Command::JoinTeam {
+                        invite_code,
+                        member_name,
+                        response,
+                    } => {
+                        command::join::handle(&mut self, &invite_code, member_name, response).await
+                    }
+                    Command::Login {
+                        typed_id,
+                        pin,
+                        member_name,
+                        response,
+                    } => {
+                        command::login::handle(&mut self, &typed_id, &pin, member_name, response)
+                            .await;
+                    }
+                    Command::ResetPin {
+                        typed_id,
+                        pin,
                         response,
-                    } => command::new_session::handle(&mut self, team_name, response).await,
+                    } => {
+                        command::login::handle_reset_pin(&mut self, &typed_id, pin, response).await;
+                    }
                     Command::GetCurrentClue { id, response } => {
                         command::current_clue::handle(&mut self, &id, response).await
                     }
-                    Command::HintCurrentClue { id } => {
-                        command::hint::handle_hint(&mut self, &id).await
+                    Command::HintCurrentClue { id, member } => {
+                        command::hint::handle_hint(&mut self, &id, member).await
+                    }
+                    Command::RevealCurrentItem { id, member } => {
+                        command::hint::handle_reveal(&mut self, &id, member).await
                     }
-                    Command::RevealCurrentItem { id } => {
-                        command::hint::handle_reveal(&mut self, &id).await
+                    Command::SkipClue { id, member } => {
+                        command::hint::handle_skip(&mut self, &id, member).await
                     }
-                    Command::SkipClue { id } => command::hint::handle_skip(&mut self, &id).await,
                     Command::AnswerCurrentClue {
                         id,
+                        member,
                         guess,
                         response,
                     } => {
-                        command::answer::handle(&mut self, &id, &guess, response).await;
+                        command::answer::handle(&mut self, &id, member, &guess, response).await;
+                    }
+                    Command::Leaderboard {
+                        hunt,
+                        maybe_id,
+                        live,
+                        response,
+                    } => {
+                        command::leader_board::handle(&self, hunt, maybe_id, live, response);
                     }
-                    Command::Leaderboard { maybe_id, response } => {
-                        command::leader_board::handle(&self, maybe_id, response);
+                    Command::RequestHelp {
+                        id,
+                        member,
+                        message,
+                        response,
+                    } => {
+                        command::help::handle_request(&mut self, &id, member, &message, response)
+                            .await;
+                    }
+                    Command::HelpQueue { response } => {
+                        command::help::handle_queue(&self, response);
+                    }
+                    Command::ReplyToHelp {
+                        id,
+                        clue_code,
+                        message,
+                        points,
+                        response,
+                    } => {
+                        command::help::handle_reply(
+                            &mut self, &id, &clue_code, &message, points, response,
+                        )
+                        .await;
+                    }
+                    Command::SubmitPhoto {
+                        id,
+                        member,
+                        file,
+                        response,
+                    } => {
+                        command::photo::handle_submit(&mut self, &id, member, file, response).await;
+                    }
+                    Command::PhotoQueue { response } => {
+                        command::photo::handle_queue(&self, response);
+                    }
+                    Command::ReviewPhoto {
+                        id,
+                        clue_code,
+                        approved,
+                        response,
+                    } => {
+                        command::photo::handle_review(
+                            &mut self, &id, &clue_code, approved, response,
+                        )
+                        .await;
+                    }
+                    Command::Announce {
+                        message,
+                        expires,
+                        teams,
+                        response,
+                    } => {
+                        command::announce::handle(&mut self, message, expires, teams, response)
+                            .await;
+                    }
+                    Command::RemoveAnnouncement { id, response } => {
+                        command::announce::handle_remove(&mut self, id, response).await;
+                    }
+                    Command::Spectator { hunt, response } => {
+                        command::spectator::handle(&self, hunt, response);
+                    }
+                    Command::FreezeLeaderboard {
+                        hunt,
+                        freeze,
+                        response,
+                    } => {
+                        command::freeze::handle(&mut self, hunt, freeze, response).await;
+                    }
+                    Command::TeamCodes { id, response } => {
+                        command::team_codes::handle(&self, &id, response);
+                    }
+                    Command::TeamInfo { id, response } => {
+                        command::team_info::handle(&self, &id, response);
+                    }
+                    Command::ReloadClues {
+                        confirm_answer_changes,
+                        response,
+                    } => {
+                        command::reload::handle(&mut self, confirm_answer_changes, response).await;
+                    }
+                    Command::Arrangements { response } => {
+                        command::arrangements::handle(&self, response);
+                    }
+                    Command::AnomalyReport { response } => {
+                        command::anomalies::handle(&self, response);
+                    }
+                    Command::Statistics { response } => {
+                        command::statistics::handle(&self, response);
+                    }
+                    Command::Results { response } => {
+                        command::results::handle(&self, response);
+                    }
+                    Command::ScoreBreakdown { id, response } => {
+                        command::progress::handle(&self, &id, response);
                     }
                 }
             }
         })
     }
 
-    fn load_persisted_state(
-        config: &Config,
-    ) -> Option<(HashMap<SessionId, TeamSession>, HashSet<TeamName>)> {
+    fn load_persisted_state(config: &Config) -> Option<PersistedState> {
         let contents = std::fs::read_to_string(Path::new(&config.state_persist_path)).ok()?;
         let state: serialization::SerializableState<'static> =
             serde_json::from_str(&contents).ok()?;
         let result = state.convert();
-        if let Some((_, names)) = &result {
-            tracing::info!("Loaded previous state including team names: {names:?}");
+        if let Some(persisted) = &result {
+            tracing::info!(
+                "Loaded previous state including team names: {:?}",
+                persisted.team_names
+            );
         }
         result
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+/// The team sessions persisted at `path`, e.g. to export results after the server stopped.
+pub fn read_persisted_sessions(path: &Path) -> io::Result<HashMap<SessionId, TeamSession>> {
+    let contents = std::fs::read_to_string(path)?;
+    let state: serialization::SerializableState<'static> = serde_json::from_str(&contents)?;
+    let persisted = state
+        .convert()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Invalid persisted state"))?;
+    Ok(persisted.sessions)
+}
+
+/// Apply `update` to a team's session in the state persisted at `path`. The server must
+/// be stopped first, or it overwrites the change the next time it persists its state.
+pub fn update_persisted_session<T>(
+    path: &Path,
+    id: SessionId,
+    update: impl FnOnce(&mut Session) -> T,
+) -> anyhow::Result<T> {
+    let contents = std::fs::read_to_string(path)?;
+    let mut state: serialization::SerializableState<'static> = serde_json::from_str(&contents)?;
+    let result = state
+        .update_session(id, update)?
+        .ok_or_else(|| anyhow::anyhow!("No team has session ID {id}"))?;
+    // Write to a temporary file first, like `StateWriter`, so the state is never half-written.
+    let mut temp_path = path.to_path_buf();
+    temp_path.set_extension("tmp");
+    std::fs::write(&temp_path, serde_json::to_string_pretty(&state)?)?;
+    std::fs::rename(&temp_path, path)?;
+    Ok(result)
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize)]
 pub struct TeamName(String);
 
 impl TeamName {
@@ -0,0 +1,71 @@
+//! Optional team PINs, which must be entered when logging in on a new device.
+
+use {
+    rand::Rng,
+    serde::{Deserialize, Serialize},
+    sha3::{Digest, Sha3_256},
+    std::collections::HashMap,
+    tokio::sync::watch,
+    treasure_hunt_core::session::SessionId,
+};
+
+/// How many times each team's PIN was reset, for routes to check session cookies
+/// against. Teams whose PIN was never reset are left out.
+pub type PinGenerations = watch::Receiver<HashMap<SessionId, u32>>;
+
+const MIN_PIN_LEN: usize = 4;
+const MAX_PIN_LEN: usize = 64;
+
+/// A salted hash of a team's PIN. The PIN itself is never stored.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PinHash {
+    #[serde(with = "hex")]
+    salt: [u8; 16],
+    #[serde(with = "hex")]
+    hash: [u8; 32],
+}
+
+impl PinHash {
+    pub fn new(pin: &str) -> anyhow::Result<Self> {
+        let pin = pin.trim();
+        if pin.len() < MIN_PIN_LEN {
+            anyhow::bail!("PIN must be at least {MIN_PIN_LEN} characters long!");
+        }
+        if pin.len() > MAX_PIN_LEN {
+            anyhow::bail!("PIN too long!");
+        }
+        let salt: [u8; 16] = rand::rng().random();
+        Ok(Self {
+            hash: hash_pin(&salt, pin),
+            salt,
+        })
+    }
+
+    pub fn matches(&self, pin: &str) -> bool {
+        hash_pin(&self.salt, pin.trim()) == self.hash
+    }
+}
+
+fn hash_pin(salt: &[u8], pin: &str) -> [u8; 32] {
+    let mut hasher = Sha3_256::new();
+    hasher.update(salt);
+    hasher.update(pin.as_bytes());
+    hasher.finalize().into()
+}
+
+#[test]
+fn test_pin_hash() {
+    assert!(PinHash::new("123").is_err(), "PIN too short");
+    let pin = PinHash::new("1234").unwrap();
+    assert!(pin.matches("1234"));
+    assert!(pin.matches(" 1234 "));
+    assert!(!pin.matches("1235"));
+    assert_ne!(
+        pin,
+        PinHash::new("1234").unwrap(),
+        "Hashes of the same PIN are salted"
+    );
+
+    let json = serde_json::to_value(&pin).unwrap();
+    assert_eq!(serde_json::from_value::<PinHash>(json).unwrap(), pin);
+}
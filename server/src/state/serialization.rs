@@ -1,21 +1,60 @@
 use {
-    crate::state::{State, TeamName, TeamSession},
+    crate::state::{
+        State, TeamName, TeamSession,
+        announcement::Announcement,
+        freeze::FrozenLeaderboard,
+        hunt::{DEFAULT_HUNT_ID, HuntId},
+        member::{self, MemberName},
+        pin::PinHash,
+    },
     serde::{Deserialize, Serialize},
     std::{
         borrow::Cow,
         collections::{HashMap, HashSet},
+        time::SystemTime,
     },
-    treasure_hunt_core::session::{Session, SessionId},
+    treasure_hunt_core::session::{Session, SessionId, standing::Standing},
 };
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SerializableState<'a> {
     sessions: HashMap<String, SerializableTeamSession<'a>>,
     team_names: HashSet<Cow<'a, str>>,
+    /// The frozen leaderboard shared by all hunts, in states saved before each hunt had its own.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    frozen_leaderboard: Option<SerializableFrozenLeaderboard>,
+    #[serde(default)]
+    frozen_leaderboards: HashMap<String, SerializableFrozenLeaderboard>,
+    #[serde(default)]
+    announcements: Vec<SerializableAnnouncement>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SerializableAnnouncement {
+    id: u64,
+    message: String,
+    created: SystemTime,
+    expires: Option<SystemTime>,
+    teams: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SerializableFrozenLeaderboard {
+    since: SystemTime,
+    standings: HashMap<String, Standing>,
+}
+
+/// Everything restored from the persisted state.
+#[derive(Default)]
+pub struct PersistedState {
+    pub sessions: HashMap<SessionId, TeamSession>,
+    pub team_names: HashSet<TeamName>,
+    pub frozen_leaderboards: HashMap<HuntId, FrozenLeaderboard>,
+    pub announcements: Vec<Announcement>,
 }
 
 impl<'a> SerializableState<'a> {
-    pub fn convert(self) -> Option<(HashMap<SessionId, TeamSession>, HashSet<TeamName>)> {
+    pub fn convert(self) -> Option<PersistedState> {
         let sessions: Option<HashMap<SessionId, TeamSession>> = self
             .sessions
             .into_iter()
@@ -26,7 +65,58 @@ impl<'a> SerializableState<'a> {
             .iter()
             .map(|name| TeamName::new(name))
             .collect();
-        Some((sessions?, team_names.ok()?))
+        let sessions: HashMap<SessionId, TeamSession> = sessions?;
+        let mut frozen_leaderboards: HashMap<HuntId, FrozenLeaderboard> = self
+            .frozen_leaderboards
+            .into_iter()
+            .filter_map(|(hunt, frozen)| Some((HuntId::new(&hunt).ok()?, frozen.into())))
+            .collect();
+        // A leaderboard frozen before each hunt had its own applies to every hunt.
+        if let Some(frozen) = self.frozen_leaderboard {
+            let frozen = FrozenLeaderboard::from(frozen);
+            for team_session in sessions.values() {
+                frozen_leaderboards
+                    .entry(team_session.hunt.clone())
+                    .or_insert_with(|| frozen.clone());
+            }
+        }
+        let announcements = self
+            .announcements
+            .into_iter()
+            .map(|announcement| Announcement {
+                id: announcement.id,
+                message: announcement.message,
+                created: announcement.created,
+                expires: announcement.expires,
+                teams: announcement
+                    .teams
+                    .iter()
+                    .filter_map(|id| SessionId::new(id))
+                    .collect(),
+            })
+            .collect();
+        Some(PersistedState {
+            sessions,
+            team_names: team_names.ok()?,
+            frozen_leaderboards,
+            announcements,
+        })
+    }
+
+    /// Apply `update` to the persisted session with ID `id`, leaving the rest of the state
+    /// as it was. Returns `None` if there is no such session.
+    pub fn update_session<T>(
+        &mut self,
+        id: SessionId,
+        update: impl FnOnce(&mut Session) -> T,
+    ) -> Result<Option<T>, serde_json::Error> {
+        let Some(team_session) = self.sessions.get_mut(id.as_str()) else {
+            return Ok(None);
+        };
+        let mut session = Session::from_json(team_session.session.clone())?;
+        let result = update(&mut session);
+        team_session.session = session.to_json()?;
+        Ok(Some(result))
     }
 }
 
@@ -34,6 +124,29 @@ impl<'a> SerializableState<'a> {
 pub struct SerializableTeamSession<'a> {
     name: Cow<'a, str>,
     session: serde_json::Value,
+    #[serde(default)]
+    pin: Option<Cow<'a, PinHash>>,
+    #[serde(default)]
+    invite_code: Option<Cow<'a, str>>,
+    #[serde(default)]
+    members: Vec<Cow<'a, str>>,
+    #[serde(default)]
+    hunt: Option<Cow<'a, str>>,
+    #[serde(default)]
+    pin_generation: u32,
+}
+
+impl From<SerializableFrozenLeaderboard> for FrozenLeaderboard {
+    fn from(value: SerializableFrozenLeaderboard) -> Self {
+        Self {
+            since: value.since,
+            standings: value
+                .standings
+                .into_iter()
+                .filter_map(|(id, standing)| Some((SessionId::new(&id)?, standing)))
+                .collect(),
+        }
+    }
 }
 
 impl<'a> TryFrom<&'a State> for SerializableState<'a> {
@@ -52,6 +165,34 @@ impl<'a> TryFrom<&'a State> for SerializableState<'a> {
                 .iter()
                 .map(|name| Cow::Borrowed(name.0.as_str()))
                 .collect(),
+            frozen_leaderboard: None,
+            frozen_leaderboards: value
+                .frozen_leaderboards
+                .iter()
+                .map(|(hunt, frozen)| {
+                    let frozen = SerializableFrozenLeaderboard {
+                        since: frozen.since,
+                        standings: frozen
+                            .standings
+                            .iter()
+                            .map(|(id, standing)| (id.to_string(), standing.clone()))
+                            .collect(),
+                    };
+                    (hunt.to_string(), frozen)
+                })
+                .collect(),
+            announcements: value
+                .announcements
+                .borrow()
+                .iter()
+                .map(|announcement| SerializableAnnouncement {
+                    id: announcement.id,
+                    message: announcement.message.clone(),
+                    created: announcement.created,
+                    expires: announcement.expires,
+                    teams: announcement.teams.iter().map(|id| id.to_string()).collect(),
+                })
+                .collect(),
         })
     }
 }
@@ -63,6 +204,15 @@ impl<'a> TryFrom<&'a TeamSession> for SerializableTeamSession<'a> {
         Ok(Self {
             name: Cow::Borrowed(&value.name.0),
             session: value.session.to_json()?,
+            pin: value.pin.as_ref().map(Cow::Borrowed),
+            invite_code: Some(Cow::Borrowed(&value.invite_code)),
+            members: value
+                .members
+                .iter()
+                .map(|member| Cow::Borrowed(member.0.as_str()))
+                .collect(),
+            hunt: Some(Cow::Borrowed(value.hunt.as_str())),
+            pin_generation: value.pin_generation,
         })
     }
 }
@@ -73,6 +223,25 @@ impl<'a> TryFrom<SerializableTeamSession<'a>> for TeamSession {
     fn try_from(value: SerializableTeamSession<'a>) -> Result<Self, Self::Error> {
         let name = TeamName::new(&value.name)?;
         let session = Session::from_json(value.session)?;
-        Ok(Self { name, session })
+        let pin = value.pin.map(Cow::into_owned);
+        // Sessions saved before invite codes existed are given a new one.
+        let invite_code = value
+            .invite_code
+            .map_or_else(member::generate_invite_code, Cow::into_owned);
+        let members = value
+            .members
+            .iter()
+            .map(|member| MemberName::new(member))
+            .collect::<anyhow::Result<_>>()?;
+        let hunt = HuntId::new(value.hunt.as_deref().unwrap_or(DEFAULT_HUNT_ID))?;
+        Ok(Self {
+            name,
+            hunt,
+            session,
+            pin,
+            invite_code,
+            members,
+            pin_generation: value.pin_generation,
+        })
     }
 }
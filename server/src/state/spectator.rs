@@ -0,0 +1,64 @@
+//! What spectators see on the big screen: every team's solves, as they happen.
+
+use {
+    crate::state::hunt::HuntId,
+    tokio::sync::broadcast,
+    treasure_hunt_core::{
+        clues::status::{KnowledgeKind, Status},
+        session::Session,
+    },
+};
+
+/// Something worth telling everyone watching a hunt, e.g. that a team solved a clue.
+#[derive(Debug, Clone)]
+pub struct SpectatorEvent {
+    pub hunt: HuntId,
+    pub message: String,
+}
+
+pub type SpectatorEvents = broadcast::Sender<SpectatorEvent>;
+
+/// Describes which of the session's clues was solved and how, e.g. "clue 7 unaided".
+/// Returns `None` unless the clue with the given code is solved.
+pub fn describe_solve(session: &Session, clue_code: &[u8; 32]) -> Option<String> {
+    let (number, kind) =
+        session
+            .clues()
+            .enumerate()
+            .find_map(|(i, (clue, status))| match status {
+                Status::Solved { kind, .. } if &clue.code == clue_code => Some((i + 1, kind)),
+                _ => None,
+            })?;
+    let how = match kind {
+        KnowledgeKind::Unaided => "unaided",
+        KnowledgeKind::WithHint => "with a hint",
+        KnowledgeKind::KnowingItem => "knowing the item",
+    };
+    Some(format!("clue {number} {how}"))
+}
+
+#[test]
+fn test_describe_solve() {
+    use treasure_hunt_core::{
+        clues::{Clues, team_code::AnswerMode},
+        session::SessionId,
+    };
+
+    let clues = Clues::mock();
+    let mut session = Session::new(SessionId::new("TEST").unwrap(), clues.clone());
+    session.current_clue();
+    session.try_solve(&clues.0[0].id, &AnswerMode::Shared, None);
+    session.current_clue();
+    session.hint_current_clue(None);
+    session.try_solve(&clues.0[1].id, &AnswerMode::Shared, None);
+
+    assert_eq!(
+        describe_solve(&session, &clues.0[0].code).as_deref(),
+        Some("clue 1 unaided")
+    );
+    assert_eq!(
+        describe_solve(&session, &clues.0[1].code).as_deref(),
+        Some("clue 2 with a hint")
+    );
+    assert_eq!(describe_solve(&session, &clues.0[2].code), None);
+}
@@ -0,0 +1,85 @@
+//! Watches the clue files and reloads them when they change.
+
+use {
+    crate::{
+        config::Config,
+        state::command::{Command, reload::ReloadError},
+    },
+    std::{path::PathBuf, time::Duration, time::SystemTime},
+    tokio::{
+        sync::{mpsc, oneshot},
+        task::JoinHandle,
+    },
+};
+
+pub struct ClueWatcher {
+    paths: Vec<PathBuf>,
+    interval: Duration,
+    sender: mpsc::Sender<Command>,
+}
+
+impl ClueWatcher {
+    /// Returns `None` if watching is not enabled in the config.
+    pub fn new(config: &Config, sender: mpsc::Sender<Command>) -> Option<Self> {
+        let seconds = config.clue_reload_poll_seconds?;
+        Some(Self {
+            paths: config
+                .hunts()
+                .into_iter()
+                .map(|hunt| hunt.clues_path.into())
+                .collect(),
+            interval: Duration::from_secs(seconds.max(1)),
+            sender,
+        })
+    }
+
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut last_modified = self.modified_times().await;
+            let mut interval = tokio::time::interval(self.interval);
+            loop {
+                interval.tick().await;
+                let modified = self.modified_times().await;
+                if modified == last_modified {
+                    continue;
+                }
+                last_modified = modified;
+                tracing::info!("Clue file changed, reloading");
+                if !self.reload().await {
+                    return;
+                }
+            }
+        })
+    }
+
+    async fn modified_times(&self) -> Vec<Option<SystemTime>> {
+        let mut times = Vec::new();
+        for path in &self.paths {
+            let metadata = tokio::fs::metadata(path).await.ok();
+            times.push(metadata.and_then(|metadata| metadata.modified().ok()));
+        }
+        times
+    }
+
+    /// Returns false if the state has shut down.
+    async fn reload(&self) -> bool {
+        let (tx, rx) = oneshot::channel();
+        // Answer changes must be confirmed by an admin, so they are never applied here.
+        let command = Command::ReloadClues {
+            confirm_answer_changes: false,
+            response: tx,
+        };
+        if self.sender.send(command).await.is_err() {
+            return false;
+        }
+        match rx.await {
+            Ok(Ok(_)) => (),
+            Ok(Err(e @ ReloadError::AnswersChanged(_))) => {
+                tracing::warn!("{e}. Use the admin reload page to apply them.");
+            }
+            Ok(Err(e)) => tracing::error!("Failed to reload clues: {e}"),
+            Err(_) => return false,
+        }
+        true
+    }
+}